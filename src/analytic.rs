@@ -0,0 +1,297 @@
+//! Exact free-space solutions for radial initial data.
+//!
+//! The solution with `u(0) = f`, `u_t(0) = g` is `∂_t W[f] + W[g]`, where
+//! `W[h]` solves the problem with data `(0, h)`. With `M_h(r, ρ)` the mean
+//! of `h` over the sphere of radius `ρ` centred at distance `r` from the
+//! origin, and `D = (1/t) ∂_t`:
+//!
+//! - `N = 1` (d'Alembert): `W[h] = 1/2 ∫_{r-t}^{r+t} h(|s|) ds`,
+//! - odd `N = 2k + 1` (Kirchhoff for `N = 3`):
+//!   `W[h] = D^{k-1} (t^{2k-1} M_h(r, t)) / (1·3···(N-2))`,
+//! - even `N = 2k` (Poisson for `N = 2`, method of descent beyond):
+//!   `W[h] = D^{k-1} (N t^{N-1} ∫_0^{π/2} sin^{N-1}θ M_h(r, t sinθ) dθ) / (2·4···N)`,
+//!
+//! where the substitution `ρ = t sinθ` removes the `1/sqrt(t^2 - ρ^2)`
+//! singularity of the ball average. Every integral is evaluated by composite
+//! Gauss–Legendre quadrature and the time derivatives by fourth-order
+//! central differences.
+
+use crate::error::{Error, Result};
+use crate::grid::GridND;
+use crate::initial::InitialCondition;
+use std::f64::consts::PI;
+
+/// Positive nodes and weights of the 8-point Gauss–Legendre rule on [-1, 1].
+const GAUSS_LEGENDRE: [(f64, f64); 4] = [
+    (0.183_434_642_495_65, 0.362_683_783_378_362),
+    (0.525_532_409_916_329, 0.313_706_645_877_887_3),
+    (0.796_666_477_413_626_7, 0.222_381_034_453_374_5),
+    (0.960_289_856_497_536_3, 0.101_228_536_290_376_3),
+];
+
+/// L1, L2 and maximum norms of the difference between two fields, the first
+/// two weighted by the cell volume `delta_x^N`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ErrorNorms {
+    pub l1: f64,
+    pub l2: f64,
+    pub linf: f64,
+}
+
+impl ErrorNorms {
+    /// Norms of the pointwise errors `errors` on cells of volume `volume`.
+    pub fn from_errors(errors: impl IntoIterator<Item = f64>, volume: f64) -> Self {
+        let mut norms = ErrorNorms::default();
+        for e in errors {
+            let e = e.abs();
+            norms.l1 += e;
+            norms.l2 += e * e;
+            norms.linf = norms.linf.max(e);
+        }
+        norms.l1 *= volume;
+        norms.l2 = (norms.l2 * volume).sqrt();
+        norms
+    }
+
+    /// Error of `coarse.x_1` against `fine.x_1` at the points of the coarse
+    /// grid, which must all be points of the fine one at the same time.
+    pub fn between(coarse: &GridND, fine: &GridND) -> Result<Self> {
+        let same_domain =
+            coarse.ndim == fine.ndim && coarse.lower == fine.lower && coarse.upper == fine.upper;
+        let ratio = (fine.num_grid - 1) / (coarse.num_grid - 1);
+        if !same_domain || ratio * (coarse.num_grid - 1) != fine.num_grid - 1 {
+            return Err(Error::InvalidConfig(format!(
+                "grid of size {} does not refine grid of size {}",
+                fine.num_grid, coarse.num_grid
+            )));
+        }
+        if (coarse.time - fine.time).abs() > 1e-9 * fine.time.max(1.0) {
+            return Err(Error::InvalidConfig(format!(
+                "grids are at different times {} and {}",
+                coarse.time, fine.time
+            )));
+        }
+        let mut idx = vec![0; coarse.ndim];
+        let errors = (0..coarse.x_1.len()).map(|p| {
+            coarse.x_1.unravel(p, &mut idx);
+            idx.iter_mut().for_each(|i| *i *= ratio);
+            coarse.x_1[p] - fine.x_1.get(&idx)
+        });
+        Ok(ErrorNorms::from_errors(
+            errors,
+            coarse.delta_x.powi(coarse.ndim as i32),
+        ))
+    }
+}
+
+/// Samples of a radial function at `r = i * delta_r`, at least four of them,
+/// read back with cubic interpolation.
+#[derive(Debug, Clone)]
+pub struct RadialProfile {
+    pub delta_r: f64,
+    pub values: Vec<f64>,
+}
+
+impl RadialProfile {
+    /// Cubic Lagrange interpolation through the four nearest samples; `r` is
+    /// clamped to the sampled range.
+    pub fn value_at(&self, r: f64) -> f64 {
+        let last = self.values.len() - 1;
+        let s = (r / self.delta_r).clamp(0.0, last as f64);
+        let i = (s as usize).clamp(1, last - 2);
+        let t = s - i as f64;
+        let [a, b, c, d] = [
+            self.values[i - 1],
+            self.values[i],
+            self.values[i + 1],
+            self.values[i + 2],
+        ];
+        -t * (t - 1.0) * (t - 2.0) / 6.0 * a + (t + 1.0) * (t - 1.0) * (t - 2.0) / 2.0 * b
+            - (t + 1.0) * t * (t - 2.0) / 2.0 * c
+            + (t + 1.0) * t * (t - 1.0) / 6.0 * d
+    }
+}
+
+/// Free-space solution in `dim` dimensions for radial initial data.
+#[derive(Debug, Clone)]
+pub struct Analytic {
+    pub dim: usize,
+    pub displacement: InitialCondition,
+    pub velocity: InitialCondition,
+    /// Gauss–Legendre panels per integral; each has 8 nodes.
+    pub panels: usize,
+}
+
+/// One operator of the time-derivative chain applied to the potential.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Derivative {
+    /// `∂_t`
+    Time,
+    /// `(1/t) ∂_t`
+    Reduced,
+}
+
+impl Analytic {
+    /// Fails unless both profiles depend on `|x|` only.
+    pub fn new(
+        dim: usize,
+        displacement: InitialCondition,
+        velocity: InitialCondition,
+    ) -> Result<Self> {
+        if dim == 0 {
+            return Err(Error::InvalidConfig("dim must be at least 1".to_string()));
+        }
+        InitialCondition::check_radial(&displacement, &velocity, "the analytic solution")?;
+        Ok(Analytic {
+            dim,
+            displacement,
+            velocity,
+            panels: 32,
+        })
+    }
+
+    /// The README problem, `u(0) = u_t(0) = exp(-40 r^2)`.
+    pub fn readme(dim: usize) -> Self {
+        Analytic {
+            dim,
+            displacement: InitialCondition::default(),
+            velocity: InitialCondition::default(),
+            panels: 32,
+        }
+    }
+
+    /// `u(r, t)`.
+    pub fn evaluate(&self, r: f64, t: f64) -> f64 {
+        if t == 0.0 {
+            return self.displacement.evaluate(&[r]);
+        }
+        self.free(&self.displacement, r, t, true) + self.free(&self.velocity, r, t, false)
+    }
+
+    /// `u(r, t)` at `points >= 4` radii `0, dr, ..., radius`.
+    pub fn profile(&self, t: f64, radius: f64, points: usize) -> RadialProfile {
+        assert!(points >= 4, "a radial profile needs at least 4 points");
+        let delta_r = radius / (points as f64 - 1.0);
+        RadialProfile {
+            delta_r,
+            values: (0..points)
+                .map(|i| self.evaluate(i as f64 * delta_r, t))
+                .collect(),
+        }
+    }
+
+    /// Error of `grid.x_1` against the exact solution at `grid.time`, read
+    /// from a profile four times finer than the grid.
+    pub fn errors(&self, grid: &GridND) -> ErrorNorms {
+        let reach = grid.lower.abs().max(grid.upper.abs()) * (grid.ndim as f64).sqrt();
+        let points = (4.0 * reach / grid.delta_x).ceil() as usize + 1;
+        let exact = self.profile(grid.time, reach, points.max(4));
+        let mut idx = vec![0; grid.ndim];
+        let mut x = vec![0.0; grid.ndim];
+        let errors = (0..grid.x_1.len()).map(|p| {
+            grid.point(p, &mut idx, &mut x);
+            let r = x.iter().map(|x| x * x).sum::<f64>().sqrt();
+            grid.x_1[p] - exact.value_at(r)
+        });
+        ErrorNorms::from_errors(errors, grid.delta_x.powi(grid.ndim as i32))
+    }
+
+    /// `W[h](r, t)`, or `∂_t W[h](r, t)` if `differentiate`.
+    fn free(&self, h: &InitialCondition, r: f64, t: f64, differentiate: bool) -> f64 {
+        let n = self.dim;
+        if n == 1 {
+            let at = |s: f64| h.evaluate(&[s.abs()]);
+            return if differentiate {
+                0.5 * (at(r - t) + at(r + t))
+            } else {
+                0.5 * self
+                    .rule(r - t, r + t)
+                    .iter()
+                    .map(|&(s, w)| w * at(s))
+                    .sum::<f64>()
+            };
+        }
+        // (cos θ, weight) of the normalized surface measure sin^{N-2}θ dθ.
+        let mut sphere: Vec<(f64, f64)> = self
+            .rule(0.0, PI)
+            .into_iter()
+            .map(|(theta, w)| (theta.cos(), w * theta.sin().powi(n as i32 - 2)))
+            .collect();
+        let area: f64 = sphere.iter().map(|&(_, w)| w).sum();
+        sphere.iter_mut().for_each(|(_, w)| *w /= area);
+
+        let k = n / 2;
+        let (potential, gamma): (Box<dyn Fn(f64) -> f64 + '_>, f64) = if n % 2 == 1 {
+            (
+                Box::new(move |t: f64| t.powi(2 * k as i32 - 1) * spherical_mean(h, r, t, &sphere)),
+                (1..n - 1).step_by(2).map(|j| j as f64).product(),
+            )
+        } else {
+            // (sin θ, weight) of sin^{N-1}θ dθ on [0, π/2].
+            let ball: Vec<(f64, f64)> = self
+                .rule(0.0, 0.5 * PI)
+                .into_iter()
+                .map(|(theta, w)| (theta.sin(), w * theta.sin().powi(n as i32 - 1)))
+                .collect();
+            (
+                Box::new(move |t: f64| {
+                    let mean: f64 = ball
+                        .iter()
+                        .map(|&(s, w)| w * spherical_mean(h, r, t * s, &sphere))
+                        .sum();
+                    n as f64 * t.powi(n as i32 - 1) * mean
+                }),
+                (2..=n).step_by(2).map(|j| j as f64).product(),
+            )
+        };
+        let mut chain = vec![Derivative::Reduced; k - 1];
+        if differentiate {
+            chain.insert(0, Derivative::Time);
+        }
+        apply(&potential, t, &chain) / gamma
+    }
+
+    /// Nodes and weights of the composite Gauss–Legendre rule on `[a, b]`.
+    fn rule(&self, a: f64, b: f64) -> Vec<(f64, f64)> {
+        let width = (b - a) / self.panels as f64;
+        let mut nodes = Vec::with_capacity(8 * self.panels);
+        for panel in 0..self.panels {
+            let mid = a + (panel as f64 + 0.5) * width;
+            for &(node, weight) in GAUSS_LEGENDRE.iter() {
+                let offset = 0.5 * width * node;
+                nodes.push((mid - offset, 0.5 * width * weight));
+                nodes.push((mid + offset, 0.5 * width * weight));
+            }
+        }
+        nodes
+    }
+}
+
+/// Mean of the radial profile `h` over the sphere of radius `rho` whose centre
+/// lies at distance `r` from the origin, with `sphere` the `(cos θ, weight)`
+/// pairs of the polar angle.
+fn spherical_mean(h: &InitialCondition, r: f64, rho: f64, sphere: &[(f64, f64)]) -> f64 {
+    sphere
+        .iter()
+        .map(|&(cos, w)| {
+            let s2 = r * r + rho * rho + 2.0 * r * rho * cos;
+            w * h.evaluate(&[s2.max(0.0).sqrt()])
+        })
+        .sum()
+}
+
+/// Applies `chain[0]` to the result of the rest of the chain applied to `f`,
+/// each by fourth-order central differences in `t`.
+fn apply(f: &dyn Fn(f64) -> f64, t: f64, chain: &[Derivative]) -> f64 {
+    let Some((&first, rest)) = chain.split_first() else {
+        return f(t);
+    };
+    let h = (1e-3 * t.min(1.0)).min(0.25 * t);
+    let g = |t: f64| apply(f, t, rest);
+    let derivative =
+        (g(t - 2.0 * h) - 8.0 * g(t - h) + 8.0 * g(t + h) - g(t + 2.0 * h)) / (12.0 * h);
+    match first {
+        Derivative::Time => derivative,
+        Derivative::Reduced => derivative / t,
+    }
+}
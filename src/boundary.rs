@@ -0,0 +1,304 @@
+//! Boundary conditions on the faces of the N-dimensional cube.
+
+use crate::error::{Error, Result};
+use crate::field::Field;
+use serde::{Deserialize, Serialize};
+
+/// Condition imposed on one face of the domain.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum BoundaryCondition {
+    /// `u = value` on the face; `value = 0` is the homogeneous condition.
+    Dirichlet {
+        #[serde(default)]
+        value: f64,
+    },
+    /// Zero normal derivative: waves reflect without changing sign.
+    Neumann,
+    /// The face is identified with the opposite face of the same axis.
+    /// Both faces of an axis must be periodic.
+    Periodic,
+    /// Mur / Engquist–Majda one-way wave condition letting outgoing waves
+    /// leave the domain with little reflection.
+    Absorbing {
+        #[serde(default)]
+        order: AbsorbingOrder,
+    },
+}
+
+/// Order of the absorbing boundary condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbsorbingOrder {
+    /// `u_t = ±u_n`, exact for waves hitting the face at normal incidence.
+    First,
+    /// Engquist–Majda second-order condition, also absorbing oblique waves.
+    /// Edges and corners fall back to first order.
+    #[default]
+    Second,
+}
+
+impl Default for BoundaryCondition {
+    fn default() -> Self {
+        BoundaryCondition::Dirichlet { value: 0.0 }
+    }
+}
+
+impl BoundaryCondition {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            BoundaryCondition::Dirichlet { value } => {
+                if !value.is_finite() {
+                    return Err(Error::InvalidConfig(format!(
+                        "dirichlet value must be finite, got {}",
+                        value
+                    )));
+                }
+                Ok(())
+            }
+            BoundaryCondition::Neumann
+            | BoundaryCondition::Periodic
+            | BoundaryCondition::Absorbing { .. } => Ok(()),
+        }
+    }
+}
+
+/// One of the two faces of an axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Lower,
+    Upper,
+}
+
+/// Conditions on the lower (`[0]`) and upper (`[1]`) face of every axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Boundaries {
+    pub faces: Vec<[BoundaryCondition; 2]>,
+}
+
+impl Boundaries {
+    /// The same condition on all `2 * ndim` faces.
+    pub fn uniform(ndim: usize, condition: BoundaryCondition) -> Self {
+        Boundaries {
+            faces: vec![[condition; 2]; ndim],
+        }
+    }
+
+    pub fn dirichlet(ndim: usize, value: f64) -> Self {
+        Boundaries::uniform(ndim, BoundaryCondition::Dirichlet { value })
+    }
+
+    pub fn set(&mut self, axis: usize, side: Side, condition: BoundaryCondition) {
+        self.faces[axis][side as usize] = condition;
+    }
+
+    pub fn get(&self, axis: usize, side: Side) -> BoundaryCondition {
+        self.faces[axis][side as usize]
+    }
+
+    /// Checks every face and that periodic faces come in pairs.
+    pub fn validate(&self, ndim: usize) -> Result<()> {
+        if self.faces.len() != ndim {
+            return Err(Error::InvalidConfig(format!(
+                "boundary conditions given for {} axes, expected {}",
+                self.faces.len(),
+                ndim
+            )));
+        }
+        for (axis, [lower, upper]) in self.faces.iter().enumerate() {
+            lower.validate()?;
+            upper.validate()?;
+            let periodic = |c: &BoundaryCondition| *c == BoundaryCondition::Periodic;
+            if periodic(lower) != periodic(upper) {
+                return Err(Error::InvalidConfig(format!(
+                    "axis {} is periodic on one face only",
+                    axis
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Imposes the conditions that fix boundary values outright: Dirichlet
+    /// values, and copies of each periodic lower face onto its upper face.
+    ///
+    /// Faces are visited axis by axis, so on edges and corners shared by
+    /// Dirichlet faces with different values the face of the last axis wins.
+    pub fn apply(&self, u: &mut Field) {
+        self.impose(u, |value| value);
+    }
+
+    /// Imposes the same conditions on a time derivative or a Laplacian of
+    /// the field, which vanish on Dirichlet faces whatever their value.
+    pub fn apply_rate(&self, v: &mut Field) {
+        self.impose(v, |_| 0.0);
+    }
+
+    /// Value imposed by every Dirichlet face, zero if there is none, or `None`
+    /// if the faces impose different values.
+    pub fn dirichlet_value(&self) -> Option<f64> {
+        let mut values = self.faces.iter().flatten().filter_map(|c| match c {
+            BoundaryCondition::Dirichlet { value } => Some(*value),
+            _ => None,
+        });
+        let first = values.next().unwrap_or(0.0);
+        values.all(|value| value == first).then_some(first)
+    }
+
+    /// The same conditions with every Dirichlet value set to zero, which
+    /// give the ghost points of time derivatives and Laplacians.
+    pub fn homogeneous(&self) -> Boundaries {
+        let mut homogeneous = self.clone();
+        for condition in homogeneous.faces.iter_mut().flatten() {
+            if let BoundaryCondition::Dirichlet { value } = condition {
+                *value = 0.0;
+            }
+        }
+        homogeneous
+    }
+
+    fn impose(&self, u: &mut Field, dirichlet: impl Fn(f64) -> f64) {
+        for (axis, sides) in self.faces.iter().enumerate() {
+            let n = u.shape[axis];
+            let offset = (n - 1) * u.strides[axis];
+            for (side, condition) in sides.iter().enumerate() {
+                let i = if side == 0 { 0 } else { n - 1 };
+                match *condition {
+                    BoundaryCondition::Dirichlet { value } => {
+                        let value = dirichlet(value);
+                        for p in u.face_points(axis, i) {
+                            u[p] = value;
+                        }
+                    }
+                    BoundaryCondition::Periodic if side == 1 => {
+                        for p in u.face_points(axis, i) {
+                            u[p] = u[p - offset];
+                        }
+                    }
+                    BoundaryCondition::Neumann
+                    | BoundaryCondition::Periodic
+                    | BoundaryCondition::Absorbing { .. } => {}
+                }
+            }
+        }
+    }
+
+    /// Whether [`Boundaries::apply`] or [`Boundaries::absorb`] sets the value
+    /// at multi-index `idx`, so the scheme does not have to advance it.
+    pub fn is_imposed(&self, idx: &[usize], shape: &[usize]) -> bool {
+        self.faces
+            .iter()
+            .zip(idx.iter().zip(shape.iter()))
+            .any(|([lower, upper], (&i, &n))| {
+                (i == 0
+                    && matches!(
+                        lower,
+                        BoundaryCondition::Dirichlet { .. } | BoundaryCondition::Absorbing { .. }
+                    ))
+                    || (i == n - 1
+                        && matches!(
+                            upper,
+                            BoundaryCondition::Dirichlet { .. }
+                                | BoundaryCondition::Periodic
+                                | BoundaryCondition::Absorbing { .. }
+                        ))
+            })
+    }
+
+    /// Sets the points on absorbing faces of `new` (time level n+1) from the
+    /// already advanced interior of `new`, `current` (level n) and `previous`
+    /// (level n-1). `ratio` is `delta_t / delta_x`.
+    ///
+    /// Points on several faces are handled after the points they depend on,
+    /// with the first-order condition along the first absorbing axis.
+    pub fn absorb(&self, new: &mut Field, current: &Field, previous: &Field, ratio: f64) {
+        let ndim = new.ndim();
+        let mut idx = vec![0; ndim];
+        let on_face = |idx: &[usize], b: usize| idx[b] == 0 || idx[b] == new.shape[b] - 1;
+
+        // (number of faces the point lies on, offset)
+        let mut points = Vec::new();
+        for axis in 0..ndim {
+            for i in [0, new.shape[axis] - 1] {
+                for p in new.face_points(axis, i) {
+                    new.unravel(p, &mut idx);
+                    if (0..axis).any(|b| on_face(&idx, b)) {
+                        continue;
+                    }
+                    if self.absorbing_face(&idx, &new.shape).is_some() {
+                        points.push(((0..ndim).filter(|&b| on_face(&idx, b)).count(), p));
+                    }
+                }
+            }
+        }
+        points.sort_by_key(|&(faces, _)| faces);
+
+        let k = (ratio - 1.0) / (ratio + 1.0);
+        let c_current = 2.0 / (ratio + 1.0);
+        let c_transverse = ratio * ratio / (2.0 * (ratio + 1.0));
+        for (faces, p) in points {
+            new.unravel(p, &mut idx);
+            let Some((axis, side, order)) = self.absorbing_face(&idx, &new.shape) else {
+                continue;
+            };
+            let s = new.strides[axis];
+            let q = if side == Side::Lower { p + s } else { p - s };
+            new[p] = if order == AbsorbingOrder::Second && faces == 1 {
+                let mut transverse = 0.0;
+                for (_, &s) in new.strides.iter().enumerate().filter(|&(b, _)| b != axis) {
+                    transverse += current[p + s] - 2.0 * current[p] + current[p - s];
+                    transverse += current[q + s] - 2.0 * current[q] + current[q - s];
+                }
+                -previous[q]
+                    + k * (new[q] + previous[p])
+                    + c_current * (current[p] + current[q])
+                    + c_transverse * transverse
+            } else {
+                current[q] + k * (new[q] - current[p])
+            };
+        }
+    }
+
+    /// First absorbing face the point at `idx` lies on.
+    fn absorbing_face(
+        &self,
+        idx: &[usize],
+        shape: &[usize],
+    ) -> Option<(usize, Side, AbsorbingOrder)> {
+        for (axis, [lower, upper]) in self.faces.iter().enumerate() {
+            if idx[axis] == 0 {
+                if let BoundaryCondition::Absorbing { order } = lower {
+                    return Some((axis, Side::Lower, *order));
+                }
+            }
+            if idx[axis] == shape[axis] - 1 {
+                if let BoundaryCondition::Absorbing { order } = upper {
+                    return Some((axis, Side::Upper, *order));
+                }
+            }
+        }
+        None
+    }
+
+    /// Value of `u` at index `j` along `axis`, seen from the point `p` whose
+    /// index along `axis` is `i`. Indices outside the grid are ghost points:
+    /// odd reflection about a Dirichlet face, even reflection about a Neumann
+    /// face, and wrap-around with period `n - 1` across periodic faces.
+    pub fn neighbor(&self, u: &Field, p: usize, axis: usize, i: usize, j: isize) -> f64 {
+        let at = |j: isize| u[(p as isize + (j - i as isize) * u.strides[axis] as isize) as usize];
+        let last = u.shape[axis] as isize - 1;
+        let (condition, mirror) = if j < 0 {
+            (self.faces[axis][0], -j)
+        } else if j > last {
+            (self.faces[axis][1], 2 * last - j)
+        } else {
+            return at(j);
+        };
+        match condition {
+            BoundaryCondition::Dirichlet { value } => 2.0 * value - at(mirror),
+            BoundaryCondition::Neumann | BoundaryCondition::Absorbing { .. } => at(mirror),
+            BoundaryCondition::Periodic => at(j.rem_euclid(last)),
+        }
+    }
+}
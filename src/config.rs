@@ -0,0 +1,395 @@
+//! Simulation configuration files.
+//!
+//! A run is described by a TOML or JSON file, selected by extension:
+//!
+//! ```toml
+//! dim = 4
+//! size = 65
+//! domain = [-1.0, 1.0]
+//! stencil = "fourth"
+//! integrator = "leapfrog"
+//! courant = 0.5
+//! threads = 8
+//!
+//! [displacement]
+//! type = "gaussian"
+//! amplitude = 1.0
+//! width = 0.158
+//!
+//! [velocity]
+//! type = "zero"
+//!
+//! [boundary]
+//! type = "dirichlet"
+//! value = 0.0
+//!
+//! [[faces]]
+//! axis = 0
+//! side = "lower"
+//! type = "neumann"
+//!
+//! [pml]
+//! thickness = 10
+//! order = 2.0
+//! reflection = 1e-6
+//!
+//! [output]
+//! dir = "out/4d"
+//! frames = 1200
+//! interval = 10
+//! energy = true
+//! probes = [[0.5], [0.5, 0.5]]
+//! ```
+//!
+//! Every field is optional and falls back to [`SimulationConfig::default`].
+//! `displacement` and `velocity` take any [`InitialCondition`] profile.
+
+use crate::boundary::{Boundaries, BoundaryCondition, Side};
+use crate::error::{Error, Result};
+use crate::grid::{GridND, DEFAULT_COURANT};
+use crate::initial::InitialCondition;
+use crate::integrator::TimeIntegrator;
+use crate::pml::PmlConfig;
+use crate::spectral::Solver;
+use crate::stencil::Stencil;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Largest `size^dim` [`SimulationConfig::validate`] accepts: 8 GiB per time
+/// level, of which a grid keeps at least three.
+pub const MAX_GRID_POINTS: usize = 1 << 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationConfig {
+    /// Spatial dimension N.
+    pub dim: usize,
+    /// Grid points per axis.
+    pub size: usize,
+    /// Lower and upper bound of every axis.
+    pub domain: [f64; 2],
+    /// Finite differences or, on fully periodic domains, the spectral reference.
+    pub solver: Solver,
+    /// Order of the discrete Laplacian.
+    pub stencil: Stencil,
+    /// Time discretization.
+    pub integrator: TimeIntegrator,
+    /// Courant number `delta_t / max_time_step`, used when `delta_t` is absent.
+    pub courant: f64,
+    /// Explicit time step, overriding `courant`; must satisfy the CFL condition.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta_t: Option<f64>,
+    /// Initial displacement `u(0)`.
+    pub displacement: InitialCondition,
+    /// Initial velocity `u_t(0)`.
+    pub velocity: InitialCondition,
+    /// Condition on every face of the domain not listed in `faces`.
+    pub boundary: BoundaryCondition,
+    /// Per-face overrides of `boundary`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub faces: Vec<FaceConfig>,
+    /// Perfectly matched layer inside the faces.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pml: Option<PmlConfig>,
+    /// Worker threads of the stencil update; all cores when absent. The
+    /// results do not depend on it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<usize>,
+    pub output: OutputConfig,
+}
+
+/// Boundary condition for one or both faces of one axis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaceConfig {
+    pub axis: usize,
+    pub side: FaceSide,
+    #[serde(flatten)]
+    pub condition: BoundaryCondition,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaceSide {
+    Lower,
+    Upper,
+    Both,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Directory for frames, animations and the echoed configuration.
+    pub dir: PathBuf,
+    /// Number of frames to draw.
+    pub frames: usize,
+    /// Time steps between frames.
+    pub interval: usize,
+    /// Write the discrete energy after every step to `energy.csv`.
+    pub energy: bool,
+    /// Abort once the energy changes in one step by more than this fraction
+    /// of its initial value; implies `energy`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_energy_drift: Option<f64>,
+    /// Points at which `u` is recorded after every step into `probes.csv`;
+    /// missing trailing coordinates are zero.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub probes: Vec<Vec<f64>>,
+    /// Bin every frame by radius into `profile.csv` and draw `u(r)` into
+    /// `profile/`, see [`radial_profile`](crate::profile::radial_profile).
+    pub radial_profile: bool,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            dim: 4,
+            size: 65,
+            domain: [-1.0, 1.0],
+            solver: Solver::default(),
+            stencil: Stencil::default(),
+            integrator: TimeIntegrator::default(),
+            courant: DEFAULT_COURANT,
+            delta_t: None,
+            displacement: InitialCondition::default(),
+            velocity: InitialCondition::default(),
+            boundary: BoundaryCondition::default(),
+            faces: Vec::new(),
+            pml: None,
+            threads: None,
+            output: OutputConfig::default(),
+        }
+    }
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        OutputConfig {
+            dir: PathBuf::from("."),
+            frames: 1200,
+            interval: 10,
+            energy: false,
+            max_energy_drift: None,
+            probes: Vec::new(),
+            radial_profile: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Toml,
+    Json,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Ok(Format::Toml),
+            Some("json") => Ok(Format::Json),
+            _ => Err(Error::Format(format!(
+                "{}: expected a .toml or .json file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Reads a `.toml` or `.json` configuration file. The result is not yet validated.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let parsed = match Format::from_path(path)? {
+            Format::Toml => toml::from_str(&text).map_err(|e| e.to_string()),
+            Format::Json => serde_json::from_str(&text).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|e| Error::Format(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Writes the configuration to `path`, in the format given by its extension.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = match Format::from_path(path)? {
+            Format::Toml => toml::to_string_pretty(self).map_err(|e| e.to_string()),
+            Format::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string()),
+        };
+        let text = text.map_err(|e| Error::Format(format!("{}: {}", path.display(), e)))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Sizes the global thread pool to `threads`, if given. The pool can only
+    /// be sized once per process; asking again for the same size is allowed.
+    pub fn apply_threads(&self) -> Result<()> {
+        let Some(threads) = self.threads else {
+            return Ok(());
+        };
+        let built = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global();
+        if built.is_err() && rayon::current_num_threads() != threads {
+            return Err(Error::InvalidConfig(format!(
+                "the thread pool already runs {} threads, cannot switch to {}",
+                rayon::current_num_threads(),
+                threads
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.validate_settings()?;
+        let points = u32::try_from(self.dim)
+            .ok()
+            .and_then(|dim| self.size.checked_pow(dim))
+            .filter(|&points| points <= MAX_GRID_POINTS);
+        if points.is_none() {
+            return Err(Error::InvalidConfig(format!(
+                "size^dim = {}^{} exceeds the limit of {} grid points; lower size",
+                self.size, self.dim, MAX_GRID_POINTS
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks every value except that `size^dim` fits in [`MAX_GRID_POINTS`],
+    /// for the solvers that never allocate the full grid.
+    pub fn validate_settings(&self) -> Result<()> {
+        let invalid = |msg: String| Err(Error::InvalidConfig(msg));
+        if self.dim == 0 {
+            return invalid("dim must be at least 1".to_string());
+        }
+        if self.size < 3 {
+            return invalid(format!("size must be at least 3, got {}", self.size));
+        }
+        let width = 2 * self.stencil.radius() + 1;
+        if self.size < width {
+            return invalid(format!(
+                "size must be at least {} for the {} order stencil, got {}",
+                width, self.stencil, self.size
+            ));
+        }
+        let [lower, upper] = self.domain;
+        if !(lower.is_finite() && upper.is_finite() && lower < upper) {
+            return invalid(format!("domain [{}, {}] is empty", lower, upper));
+        }
+        if !(self.courant > 0.0 && self.courant <= 1.0) {
+            return invalid(format!("courant must be in (0, 1], got {}", self.courant));
+        }
+        if let Some(delta_t) = self.delta_t {
+            if !(delta_t.is_finite() && delta_t > 0.0) {
+                return invalid(format!("delta_t must be positive, got {}", delta_t));
+            }
+        }
+        if self.threads == Some(0) {
+            return invalid("threads must be at least 1".to_string());
+        }
+        if let Some(drift) = self.output.max_energy_drift {
+            if !(drift.is_finite() && drift > 0.0) {
+                return invalid(format!("max_energy_drift must be positive, got {}", drift));
+            }
+        }
+        for probe in &self.output.probes {
+            if probe.len() > self.dim {
+                return invalid(format!(
+                    "probe {:?} has {} coordinates, expected at most {}",
+                    probe,
+                    probe.len(),
+                    self.dim
+                ));
+            }
+            if probe.iter().any(|x| !(lower..=upper).contains(x)) {
+                return invalid(format!(
+                    "probe {:?} lies outside the domain [{}, {}]",
+                    probe, lower, upper
+                ));
+            }
+        }
+        for face in &self.faces {
+            if face.axis >= self.dim {
+                return invalid(format!(
+                    "boundary face axis {} out of range for dim {}",
+                    face.axis, self.dim
+                ));
+            }
+        }
+        let boundaries = self.boundaries();
+        boundaries.validate(self.dim)?;
+        if let Some(pml) = &self.pml {
+            pml.validate(self.size)?;
+            if boundaries
+                .faces
+                .iter()
+                .any(|sides| sides[0] == BoundaryCondition::Periodic)
+            {
+                return invalid("pml cannot be combined with periodic faces".to_string());
+            }
+        }
+        self.integrator
+            .check_compatible(&boundaries, self.pml.is_some())?;
+        if self.output.max_energy_drift.is_some() && boundaries.dirichlet_value().is_none() {
+            return invalid(
+                "max_energy_drift needs the same value on every Dirichlet face".to_string(),
+            );
+        }
+        if self.solver == Solver::Spectral
+            && (self.pml.is_some()
+                || boundaries
+                    .faces
+                    .iter()
+                    .flatten()
+                    .any(|c| *c != BoundaryCondition::Periodic))
+        {
+            return invalid(
+                "the spectral solver needs periodic faces on every axis and no pml".to_string(),
+            );
+        }
+        self.displacement.validate(self.dim)?;
+        self.velocity.validate(self.dim)?;
+        if self.output.frames == 0 {
+            return invalid("output.frames must be at least 1".to_string());
+        }
+        if self.output.interval == 0 {
+            return invalid("output.interval must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Per-face boundary conditions: `boundary` everywhere, overridden by `faces`.
+    pub fn boundaries(&self) -> Boundaries {
+        let mut boundaries = Boundaries::uniform(self.dim, self.boundary);
+        for face in self.faces.iter().filter(|f| f.axis < self.dim) {
+            let sides: &[Side] = match face.side {
+                FaceSide::Lower => &[Side::Lower],
+                FaceSide::Upper => &[Side::Upper],
+                FaceSide::Both => &[Side::Lower, Side::Upper],
+            };
+            for &side in sides {
+                boundaries.set(face.axis, side, face.condition);
+            }
+        }
+        boundaries
+    }
+
+    /// Builds and initializes the grid described by this configuration.
+    ///
+    /// Fails with [`Error::Unstable`] if `delta_t` violates the CFL condition.
+    pub fn build_grid(&self) -> Result<GridND> {
+        let [lower, upper] = self.domain;
+        let mut grid = GridND::with_domain(self.dim, self.size, lower, upper);
+        grid.boundaries = self.boundaries();
+        grid.set_stencil(self.stencil);
+        if let Some(pml) = self.pml {
+            grid.set_pml(pml)?;
+        }
+        grid.set_integrator(self.integrator)?;
+        if self.solver == Solver::Spectral {
+            grid.set_spectral()?;
+        }
+        match self.delta_t {
+            Some(delta_t) => grid.set_time_step(delta_t)?,
+            None => grid.set_courant(self.courant)?,
+        }
+        grid.initialize_from(&self.displacement, &self.velocity);
+        Ok(grid)
+    }
+}
@@ -0,0 +1,141 @@
+//! Decay of the outgoing front with distance travelled.
+//!
+//! A radial pulse spreads its energy over a sphere of area `~ t^{N-1}`, so the
+//! peak of its front falls off like `t^{-(N-1)/2}` once it has moved far from
+//! where it started. [`Decay::measure`] follows that peak with the radial
+//! solver and fits a power law to it, which checks the solver against theory
+//! and shows 4D fronts fading faster than 3D ones.
+
+use crate::error::{Error, Result};
+use crate::huygens::RadialPulse;
+use crate::initial::InitialCondition;
+use crate::radial::RadialGrid;
+
+/// `amplitude = prefactor * t^{-exponent}`, fitted by least squares in
+/// log-log coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerLaw {
+    pub exponent: f64,
+    pub prefactor: f64,
+    /// Coefficient of determination of the fit in log-log coordinates.
+    pub r_squared: f64,
+}
+
+impl PowerLaw {
+    /// Fits the `(t, amplitude)` samples with positive time and amplitude;
+    /// `None` if fewer than two distinct times remain.
+    pub fn fit(samples: &[(f64, f64)]) -> Option<PowerLaw> {
+        let logs: Vec<(f64, f64)> = samples
+            .iter()
+            .filter(|(t, a)| *t > 0.0 && *a > 0.0)
+            .map(|(t, a)| (t.ln(), a.ln()))
+            .collect();
+        if logs.len() < 2 {
+            return None;
+        }
+        let n = logs.len() as f64;
+        let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let sxx: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        let sxy: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+        if sxx == 0.0 {
+            return None;
+        }
+        let slope = sxy / sxx;
+        let intercept = mean_y - slope * mean_x;
+        let total: f64 = logs.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+        let residual: f64 = logs
+            .iter()
+            .map(|(x, y)| (y - intercept - slope * x).powi(2))
+            .sum();
+        Some(PowerLaw {
+            exponent: -slope,
+            prefactor: intercept.exp(),
+            r_squared: if total > 0.0 {
+                1.0 - residual / total
+            } else {
+                1.0
+            },
+        })
+    }
+}
+
+/// Peak history and fitted decay of one dimension.
+#[derive(Debug, Clone)]
+pub struct DecayFit {
+    pub dim: f64,
+    /// `(N - 1) / 2`.
+    pub expected: f64,
+    pub fit: PowerLaw,
+    /// `(t, max |u|)` over the front `t - a <= r <= t + a`, after every step.
+    pub peaks: Vec<(f64, f64)>,
+}
+
+/// Front tracking setup shared by every dimension of a report.
+#[derive(Debug, Clone)]
+pub struct Decay {
+    pub pulse: RadialPulse,
+    /// First time included in the fit.
+    pub start: f64,
+    /// Last time included in the fit.
+    pub until: f64,
+}
+
+impl Decay {
+    /// Checks that the data are radial and `0 < start < until`.
+    pub fn validate(&self) -> Result<()> {
+        let pulse = &self.pulse;
+        InitialCondition::check_radial(&pulse.displacement, &pulse.velocity, "the decay fit")?;
+        if !(self.start > 0.0 && self.start < self.until) {
+            return Err(Error::InvalidConfig(format!(
+                "the fit window must satisfy 0 < start < until, got {} and {}",
+                self.start, self.until
+            )));
+        }
+        Ok(())
+    }
+
+    /// Runs the radial solver in dimension `dim` until `until` and fits the
+    /// peaks from `start` on.
+    ///
+    /// The solver's outer radius `until + a` keeps the front inside the grid for
+    /// the whole run.
+    pub fn measure(&self, dim: f64) -> Result<DecayFit> {
+        self.validate()?;
+        let mut grid = self.pulse.grid(dim, self.until + self.pulse.support)?;
+
+        let mut peaks = vec![(grid.time, self.front_peak(&grid))];
+        while grid.time < self.until {
+            grid.step();
+            peaks.push((grid.time, self.front_peak(&grid)));
+        }
+        let window: Vec<(f64, f64)> = peaks
+            .iter()
+            .copied()
+            .filter(|(t, _)| (self.start..=self.until).contains(t))
+            .collect();
+        let fit = PowerLaw::fit(&window).ok_or_else(|| {
+            Error::InvalidConfig(format!(
+                "no two steps with a nonzero front between t = {} and {}",
+                self.start, self.until
+            ))
+        })?;
+        Ok(DecayFit {
+            dim,
+            expected: (dim - 1.0) / 2.0,
+            fit,
+            peaks,
+        })
+    }
+
+    /// Largest `|u_1|` over the points within `a` of `r = t`.
+    fn front_peak(&self, grid: &RadialGrid) -> f64 {
+        let support = self.pulse.support;
+        let first = ((grid.time - support) / grid.delta_r).floor().max(0.0) as usize;
+        let last = ((grid.time + support) / grid.delta_r).ceil() as usize;
+        grid.u_1[first.min(grid.num_points - 1)..=last.min(grid.num_points - 1)]
+            .iter()
+            .map(|u| u.abs())
+            .fold(0.0, f64::max)
+    }
+}
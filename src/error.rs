@@ -0,0 +1,56 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// A configuration file could not be parsed or written.
+    Format(String),
+    /// A configuration value is out of range or inconsistent.
+    InvalidConfig(String),
+    /// The time step violates the CFL condition `delta_t <= limit`.
+    Unstable {
+        delta_t: f64,
+        limit: f64,
+    },
+    /// A frame, chart or animation could not be drawn, read or written.
+    Render(String),
+    /// The discrete energy changed by `drift`, relative to its initial value,
+    /// in the step ending at `time`, more than the allowed `limit`.
+    EnergyDrift {
+        time: f64,
+        drift: f64,
+        limit: f64,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Format(msg) => write!(f, "{}", msg),
+            Error::InvalidConfig(msg) => write!(f, "invalid configuration: {}", msg),
+            Error::Render(msg) => write!(f, "rendering failed: {}", msg),
+            Error::Unstable { delta_t, limit } => write!(
+                f,
+                "time step {} violates the CFL condition delta_t <= {}",
+                delta_t, limit
+            ),
+            Error::EnergyDrift { time, drift, limit } => write!(
+                f,
+                "energy drifted by {:e} of its initial value in the step to t = {}, above {:e}",
+                drift, time, limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,162 @@
+use rayon::prelude::*;
+use std::ops::{Index, IndexMut, Range};
+
+/// Fewest points a parallel task works on; smaller slabs are grouped so
+/// that thin grids, e.g. 1D ones, do not spawn a task per point.
+pub const MIN_TASK_POINTS: usize = 4096;
+
+/// Scalar field stored row-major in one contiguous `Vec<f64>`.
+///
+/// The last axis is the fastest-varying one, so `strides[ndim - 1] == 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub shape: Vec<usize>,
+    pub strides: Vec<usize>,
+    pub data: Vec<f64>,
+}
+
+impl Field {
+    pub fn new(shape: &[usize]) -> Self {
+        let mut strides = vec![1; shape.len()];
+        for a in (0..shape.len().saturating_sub(1)).rev() {
+            strides[a] = strides[a + 1] * shape[a + 1];
+        }
+        Field {
+            shape: shape.to_vec(),
+            strides,
+            data: vec![0.0; shape.iter().product()],
+        }
+    }
+
+    /// Field with `num_grid` points along each of `ndim` axes.
+    pub fn cube(ndim: usize, num_grid: usize) -> Self {
+        Field::new(&vec![num_grid; ndim])
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Flat offset of the multi-index `idx`.
+    pub fn offset(&self, idx: &[usize]) -> usize {
+        idx.iter()
+            .zip(self.strides.iter())
+            .map(|(i, s)| i * s)
+            .sum()
+    }
+
+    /// Multi-index of the flat offset `p`, written into `idx`.
+    pub fn unravel(&self, p: usize, idx: &mut [usize]) {
+        for ((i, &s), &n) in idx
+            .iter_mut()
+            .zip(self.strides.iter())
+            .zip(self.shape.iter())
+        {
+            *i = p / s % n;
+        }
+    }
+
+    pub fn get(&self, idx: &[usize]) -> f64 {
+        self.data[self.offset(idx)]
+    }
+
+    pub fn set(&mut self, idx: &[usize], value: f64) {
+        let p = self.offset(idx);
+        self.data[p] = value;
+    }
+
+    /// Flat offsets of every point whose index along `axis` is `i`.
+    pub fn face_points(&self, axis: usize, i: usize) -> impl Iterator<Item = usize> {
+        let inner = self.strides[axis];
+        let n = self.shape[axis];
+        let outer = self.len() / (inner * n);
+        (0..outer).flat_map(move |o| {
+            let base = (o * n + i) * inner;
+            base..base + inner
+        })
+    }
+
+    /// Calls `f` with the flat offset of every point at least `margin` points
+    /// away from every face, innermost axis fastest.
+    pub fn for_each_inner(&self, margin: usize, f: impl FnMut(usize)) {
+        let n = self.shape.first().copied().unwrap_or(0);
+        self.for_each_inner_in(margin, 0..n, f);
+    }
+
+    /// Like [`Field::for_each_inner`], restricted to the slab of points whose
+    /// index along the first axis lies in `slab`.
+    pub fn for_each_inner_in(&self, margin: usize, slab: Range<usize>, mut f: impl FnMut(usize)) {
+        if self.shape.iter().any(|&n| n < 2 * margin + 1) {
+            return;
+        }
+        let ndim = self.ndim();
+        let first = slab.start.max(margin);
+        let end = slab.end.min(self.shape[0] - margin);
+        if first >= end {
+            return;
+        }
+        let mut idx = vec![margin; ndim];
+        idx[0] = first;
+        let mut p: usize = self.offset(&idx);
+        'outer: loop {
+            f(p);
+            let mut a = ndim;
+            loop {
+                if a == 0 {
+                    break 'outer;
+                }
+                a -= 1;
+                idx[a] += 1;
+                p += self.strides[a];
+                let upper = if a == 0 { end } else { self.shape[a] - margin };
+                if idx[a] < upper {
+                    continue 'outer;
+                }
+                if a == 0 {
+                    break 'outer;
+                }
+                idx[a] = margin;
+                p -= (self.shape[a] - 2 * margin) * self.strides[a];
+            }
+        }
+    }
+
+    /// Number of consecutive slabs of the first axis that make up one
+    /// parallel task: one slab, or enough to hold [`MIN_TASK_POINTS`] points.
+    pub fn slabs_per_task(&self) -> usize {
+        let slab = self.strides.first().copied().unwrap_or(1).max(1);
+        MIN_TASK_POINTS.div_ceil(slab)
+    }
+
+    /// Copies `other`, which has the same shape, in parallel over groups of
+    /// [`Field::slabs_per_task`] slabs of the first axis.
+    pub fn copy_from(&mut self, other: &Field) {
+        let chunk = self.slabs_per_task() * self.strides.first().copied().unwrap_or(1).max(1);
+        self.data
+            .par_chunks_mut(chunk)
+            .zip(other.data.par_chunks(chunk))
+            .for_each(|(dst, src)| dst.copy_from_slice(src));
+    }
+}
+
+impl Index<usize> for Field {
+    type Output = f64;
+
+    fn index(&self, p: usize) -> &f64 {
+        &self.data[p]
+    }
+}
+
+impl IndexMut<usize> for Field {
+    fn index_mut(&mut self, p: usize) -> &mut f64 {
+        &mut self.data[p]
+    }
+}
@@ -0,0 +1,557 @@
+use crate::boundary::{Boundaries, BoundaryCondition};
+use crate::error::{Error, Result};
+use crate::field::Field;
+use crate::initial::InitialCondition;
+use crate::integrator::TimeIntegrator;
+use crate::pml::{Pml, PmlConfig};
+use crate::spectral::Spectral;
+use crate::stencil::Stencil;
+use rayon::prelude::*;
+
+/// Courant number used by [`GridND::new`] and [`GridND::with_domain`].
+pub const DEFAULT_COURANT: f64 = 0.5;
+
+/// Wave field on the N-dimensional cube [lower, upper]^N.
+#[derive(Debug)]
+pub struct GridND {
+    pub ndim: usize,
+    pub delta_t: f64,
+    pub delta_x: f64,
+    pub num_grid: usize,
+    pub lower: f64,
+    pub upper: f64,
+    /// Time of the current level `x_1`.
+    pub time: f64,
+    pub boundaries: Boundaries,
+    /// Spatial discretization of the Laplacian.
+    pub stencil: Stencil,
+    /// Time discretization, see [`GridND::set_integrator`].
+    pub integrator: TimeIntegrator,
+    /// Absorbing layer inside the faces, if any.
+    pub pml: Option<Pml>,
+    /// Exact Fourier evolution replacing the finite differences, see
+    /// [`GridND::set_spectral`].
+    pub spectral: Option<Spectral>,
+    pub x_1: Field,
+    pub x_2: Field,
+    /// Level being computed. [`GridND::step`] rotates the three levels by
+    /// swapping them instead of copying, so between steps `tmp` holds stale
+    /// values.
+    pub tmp: Field,
+    /// Velocity `u_t` at the time of `x_1`, kept by the one-step integrators
+    /// and empty otherwise.
+    pub v: Field,
+    /// Work fields of the integrator.
+    scratch: Vec<Field>,
+}
+
+impl GridND {
+    pub fn new(ndim: usize, dim: usize) -> Self {
+        GridND::with_domain(ndim, dim, -1.0, 1.0)
+    }
+
+    pub fn with_domain(ndim: usize, dim: usize, lower: f64, upper: f64) -> Self {
+        let delta_x = (upper - lower) / (dim as f64 - 1.0);
+        GridND {
+            ndim,
+            delta_t: DEFAULT_COURANT * delta_x / (ndim as f64).sqrt(),
+            delta_x,
+            num_grid: dim,
+            lower,
+            upper,
+            time: 0.0,
+            boundaries: Boundaries::dirichlet(ndim, 0.0),
+            stencil: Stencil::Second,
+            integrator: TimeIntegrator::Leapfrog,
+            pml: None,
+            spectral: None,
+            x_1: Field::cube(ndim, dim),
+            x_2: Field::cube(ndim, dim),
+            tmp: Field::cube(ndim, dim),
+            v: Field::cube(ndim, 0),
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Largest stable time step: `2 delta_x / sqrt(N S)` for leapfrog, with `S`
+    /// the stencil's [`Stencil::symbol_max`] (`delta_x / sqrt(N)` for the 3-point
+    /// stencil), times the integrator's [`TimeIntegrator::stability_factor`].
+    pub fn max_time_step(&self) -> f64 {
+        2.0 * self.delta_x / (self.ndim as f64 * self.stencil.symbol_max()).sqrt()
+            * self.integrator.stability_factor()
+    }
+
+    /// Courant number `delta_t / max_time_step()`; the scheme is stable up to 1.
+    pub fn courant(&self) -> f64 {
+        self.delta_t / self.max_time_step()
+    }
+
+    /// Sets `delta_t`, refusing steps that violate the N-dimensional CFL condition.
+    pub fn set_time_step(&mut self, delta_t: f64) -> Result<()> {
+        let limit = self.max_time_step();
+        if !(delta_t > 0.0 && delta_t <= limit) {
+            return Err(Error::Unstable { delta_t, limit });
+        }
+        self.delta_t = delta_t;
+        Ok(())
+    }
+
+    /// Sets `delta_t` from a Courant number in (0, 1].
+    pub fn set_courant(&mut self, courant: f64) -> Result<()> {
+        self.set_time_step(courant * self.max_time_step())
+    }
+
+    /// Switches the Laplacian to `stencil`, keeping the Courant number, so
+    /// `delta_t` follows the new CFL limit.
+    pub fn set_stencil(&mut self, stencil: Stencil) {
+        let courant = self.courant();
+        self.stencil = stencil;
+        self.delta_t = courant * self.max_time_step();
+    }
+
+    /// Switches the time integrator, keeping the Courant number like
+    /// [`GridND::set_stencil`]. Call before initializing the grid.
+    ///
+    /// Fails if the boundaries or the PML need leapfrog.
+    pub fn set_integrator(&mut self, integrator: TimeIntegrator) -> Result<()> {
+        integrator.check_compatible(&self.boundaries, self.pml.is_some())?;
+        let courant = self.courant();
+        self.integrator = integrator;
+        self.delta_t = courant * self.max_time_step();
+
+        let (ndim, n) = (self.ndim, self.num_grid);
+        let work = match integrator {
+            TimeIntegrator::Leapfrog => 0,
+            TimeIntegrator::Verlet => 1,
+            TimeIntegrator::ModifiedEquation => 2,
+            TimeIntegrator::Rk4 => 4,
+        };
+        self.v = Field::cube(ndim, if integrator.is_one_step() { n } else { 0 });
+        self.scratch = vec![Field::cube(ndim, n); work];
+        Ok(())
+    }
+
+    /// Surrounds the domain with a perfectly matched layer.
+    ///
+    /// Fails if the layer does not fit the grid, if a face is periodic, or if
+    /// the integrator or the spectral solver would skip the damping.
+    pub fn set_pml(&mut self, config: PmlConfig) -> Result<()> {
+        config.validate(self.num_grid)?;
+        let periodic = self
+            .boundaries
+            .faces
+            .iter()
+            .flatten()
+            .any(|c| *c == BoundaryCondition::Periodic);
+        if periodic {
+            return Err(Error::InvalidConfig(
+                "pml cannot be combined with periodic faces".to_string(),
+            ));
+        }
+        if self.spectral.is_some() {
+            return Err(Error::InvalidConfig(
+                "the spectral solver needs periodic faces on every axis and no pml".to_string(),
+            ));
+        }
+        self.integrator.check_compatible(&self.boundaries, true)?;
+        self.pml = Some(Pml::new(config, &self.x_1.shape, self.delta_x));
+        Ok(())
+    }
+
+    /// Replaces the finite differences by the exact evolution of every
+    /// Fourier mode. Every face must be periodic and there must be no PML;
+    /// the stencil and the integrator are then unused, and `delta_t` only sets
+    /// the spacing of the steps. Call before initializing the grid.
+    pub fn set_spectral(&mut self) -> Result<()> {
+        let periodic = self
+            .boundaries
+            .faces
+            .iter()
+            .flatten()
+            .all(|c| *c == BoundaryCondition::Periodic);
+        if !periodic || self.pml.is_some() {
+            return Err(Error::InvalidConfig(
+                "the spectral solver needs periodic faces on every axis and no pml".to_string(),
+            ));
+        }
+        self.spectral = Some(Spectral::new(
+            self.ndim,
+            self.num_grid,
+            self.upper - self.lower,
+        ));
+        Ok(())
+    }
+
+    pub fn coordinate(&self, i: usize) -> f64 {
+        self.lower + self.delta_x * i as f64
+    }
+
+    /// The README problem: `u(0) = u_t(0) = exp(-40 r^2)`.
+    pub fn initialize(&mut self) {
+        let gaussian = InitialCondition::default();
+        self.initialize_from(&gaussian, &gaussian);
+    }
+
+    pub fn initialize_from(
+        &mut self,
+        displacement: &InitialCondition,
+        velocity: &InitialCondition,
+    ) {
+        self.initialize_with(|x| displacement.evaluate(x), |x| velocity.evaluate(x));
+    }
+
+    /// Sets `x_2 = u(0) = displacement` and builds `x_1 = u(delta_t)` from the
+    /// Taylor start `u^1 = u^0 + dt v + dt^2 / 2 Δu^0`, `v = velocity`, with the
+    /// terms `dt^3 / 6 Δv + dt^4 / 24 Δ^2 u^0` added for the fourth-order scheme.
+    ///
+    /// The one-step integrators instead start from `x_1 = u(0)`, `v = velocity`
+    /// at time 0, and so does the spectral solver.
+    pub fn initialize_with(
+        &mut self,
+        displacement: impl Fn(&[f64]) -> f64,
+        velocity: impl Fn(&[f64]) -> f64,
+    ) {
+        let mut idx = vec![0; self.ndim];
+        let mut x = vec![0.0; self.ndim];
+        for p in 0..self.x_2.len() {
+            self.point(p, &mut idx, &mut x);
+            self.x_2[p] = displacement(&x);
+            self.tmp[p] = velocity(&x);
+        }
+        self.boundaries.apply(&mut self.x_2);
+        self.boundaries.apply_rate(&mut self.tmp);
+        self.x_1.data.copy_from_slice(&self.x_2.data);
+        if let Some(spectral) = &mut self.spectral {
+            spectral.set_initial(&self.x_2, &self.tmp);
+            self.time = 0.0;
+            return;
+        }
+
+        let dt = self.delta_t;
+        let scale = (self.delta_x * self.delta_x).recip();
+        if self.integrator.is_one_step() {
+            self.v.data.copy_from_slice(&self.tmp.data);
+            if self.integrator == TimeIntegrator::Verlet {
+                let accel = &mut self.scratch[0];
+                laplacian_into(&self.x_1, &self.boundaries, self.stencil, scale, accel);
+            }
+            self.time = 0.0;
+            return;
+        }
+
+        let (x_1, x_2, v) = (&mut self.x_1, &self.x_2, &self.tmp);
+        if self.integrator == TimeIntegrator::ModifiedEquation {
+            let homogeneous = self.boundaries.homogeneous();
+            let [lap_u, lap_v] = &mut self.scratch[..] else {
+                unreachable!("the modified equation keeps two work fields")
+            };
+            laplacian_into(x_2, &self.boundaries, self.stencil, scale, lap_u);
+            laplacian_into(v, &homogeneous, self.stencil, scale, lap_v);
+            for_each_laplacian(lap_u, &homogeneous, self.stencil, |p, lap| {
+                x_1[p] += dt.powi(3) / 6.0 * lap_v[p] + dt.powi(4) / 24.0 * scale * lap;
+            });
+        }
+        for_each_laplacian(x_2, &self.boundaries, self.stencil, |p, lap| {
+            x_1[p] += dt * v[p] + 0.5 * dt * dt * scale * lap;
+        });
+        self.boundaries.apply(&mut self.x_1);
+        self.time = dt;
+    }
+
+    /// Multi-index and coordinates of the flat offset `p`.
+    pub fn point(&self, p: usize, idx: &mut [usize], x: &mut [f64]) {
+        self.x_1.unravel(p, idx);
+        for (x, &i) in x.iter_mut().zip(idx.iter()) {
+            *x = self.coordinate(i);
+        }
+    }
+
+    pub fn step(&mut self) {
+        if let Some(spectral) = &mut self.spectral {
+            std::mem::swap(&mut self.x_1, &mut self.x_2);
+            spectral.evaluate(self.time + self.delta_t, &mut self.x_1);
+            self.boundaries.apply(&mut self.x_1);
+        } else {
+            match self.integrator {
+                TimeIntegrator::Leapfrog | TimeIntegrator::ModifiedEquation => self.step_leapfrog(),
+                TimeIntegrator::Verlet => self.step_verlet(),
+                TimeIntegrator::Rk4 => self.step_rk4(),
+            }
+        }
+        self.time += self.delta_t;
+    }
+
+    /// `u^{n+1} = 2u^n - u^{n-1} + dt^2 Δu^n`, plus `dt^4 / 12 Δ^2 u^n` for the
+    /// modified equation.
+    fn step_leapfrog(&mut self) {
+        let coef = self.delta_t * self.delta_t / (self.delta_x * self.delta_x);
+        let (x_1, x_2, tmp) = (&self.x_1, &self.x_2, &mut self.tmp);
+        if self.integrator == TimeIntegrator::ModifiedEquation {
+            let lap_u = &mut self.scratch[0];
+            laplacian_into(x_1, &self.boundaries, self.stencil, 1.0, lap_u);
+            let lap_u = &*lap_u;
+            let homogeneous = self.boundaries.homogeneous();
+            update_laplacian(lap_u, &homogeneous, self.stencil, tmp, |p, lap| {
+                2.0 * x_1[p] - x_2[p] + coef * lap_u[p] + coef * coef / 12.0 * lap
+            });
+        } else {
+            update_laplacian(x_1, &self.boundaries, self.stencil, tmp, |p, lap| {
+                2.0 * x_1[p] - x_2[p] + coef * lap
+            });
+        }
+        if let Some(pml) = &mut self.pml {
+            pml.correct(
+                &mut self.tmp,
+                &self.x_1,
+                &self.boundaries,
+                self.stencil,
+                self.delta_t,
+                self.delta_x,
+            );
+        }
+        let ratio = self.delta_t / self.delta_x;
+        self.boundaries
+            .absorb(&mut self.tmp, &self.x_1, &self.x_2, ratio);
+        self.boundaries.apply(&mut self.tmp);
+        // Rotate the levels: u^n moves to x_2, u^{n+1} to x_1, and the storage
+        // of u^{n-1} becomes the next tmp, which every step overwrites.
+        std::mem::swap(&mut self.x_2, &mut self.x_1);
+        std::mem::swap(&mut self.x_1, &mut self.tmp);
+    }
+
+    /// Kick `v += dt / 2 Δu`, drift `u += dt v`, kick again with the new `Δu`,
+    /// which is kept for the next step.
+    fn step_verlet(&mut self) {
+        let dt = self.delta_t;
+        let scale = (self.delta_x * self.delta_x).recip();
+        let accel = &mut self.scratch[0];
+        for (v, a) in self.v.data.iter_mut().zip(accel.data.iter()) {
+            *v += 0.5 * dt * a;
+        }
+        std::mem::swap(&mut self.x_1, &mut self.x_2);
+        let new = self.x_1.data.iter_mut();
+        for (u, (old, v)) in new.zip(self.x_2.data.iter().zip(self.v.data.iter())) {
+            *u = old + dt * v;
+        }
+        self.boundaries.apply(&mut self.x_1);
+        laplacian_into(&self.x_1, &self.boundaries, self.stencil, scale, accel);
+        for (v, a) in self.v.data.iter_mut().zip(accel.data.iter()) {
+            *v += 0.5 * dt * a;
+        }
+    }
+
+    /// Classical Runge–Kutta on `(u, v)`, with the stage displacement in `tmp`.
+    fn step_rk4(&mut self) {
+        let dt = self.delta_t;
+        let scale = (self.delta_x * self.delta_x).recip();
+        let [vs, kv, acc_u, acc_v] = &mut self.scratch[..] else {
+            unreachable!("rk4 keeps four work fields")
+        };
+        std::mem::swap(&mut self.x_1, &mut self.x_2);
+        let (new, u) = (&mut self.x_1, &self.x_2);
+        let (v, us) = (&mut self.v, &mut self.tmp);
+        us.copy_from(u);
+        vs.data.copy_from_slice(&v.data);
+        acc_u.data.fill(0.0);
+        acc_v.data.fill(0.0);
+        // (weight of the stage, offset of the next stage)
+        for (weight, c) in [(1.0, 0.5), (2.0, 0.5), (2.0, 1.0), (1.0, 0.0)] {
+            laplacian_into(us, &self.boundaries, self.stencil, scale, kv);
+            for p in 0..u.len() {
+                acc_u[p] += weight * vs[p];
+                acc_v[p] += weight * kv[p];
+                us[p] = u[p] + c * dt * vs[p];
+                vs[p] = v[p] + c * dt * kv[p];
+            }
+            self.boundaries.apply(us);
+        }
+        for p in 0..u.len() {
+            new[p] = u[p] + dt / 6.0 * acc_u[p];
+            v[p] += dt / 6.0 * acc_v[p];
+        }
+        self.boundaries.apply(new);
+    }
+
+    /// Values of `x_1` along `axis`, through the center of every other axis.
+    pub fn center_line(&self, axis: usize) -> Vec<f64> {
+        let base: usize = (0..self.ndim)
+            .filter(|&a| a != axis)
+            .map(|a| self.num_grid / 2 * self.x_1.strides[a])
+            .sum();
+        (0..self.num_grid)
+            .map(|i| self.x_1[base + i * self.x_1.strides[axis]])
+            .collect()
+    }
+
+    /// Multilinear interpolation of `x_1` at the point `x`, whose missing
+    /// trailing coordinates are zero; coordinates are clamped to the domain.
+    pub fn value_at(&self, x: &[f64]) -> f64 {
+        let n = self.num_grid;
+        let mut base = 0;
+        let mut frac = vec![0.0; self.ndim];
+        for (a, f) in frac.iter_mut().enumerate() {
+            let c = x.get(a).copied().unwrap_or(0.0);
+            let s = ((c - self.lower) / self.delta_x).clamp(0.0, (n - 1) as f64);
+            let i = (s as usize).min(n - 2);
+            *f = s - i as f64;
+            base += i * self.x_1.strides[a];
+        }
+        (0..1usize << self.ndim)
+            .map(|corner| {
+                let mut weight = 1.0;
+                let mut p = base;
+                for (a, &f) in frac.iter().enumerate() {
+                    if corner >> a & 1 == 1 {
+                        weight *= f;
+                        p += self.x_1.strides[a];
+                    } else {
+                        weight *= 1.0 - f;
+                    }
+                }
+                weight * self.x_1[p]
+            })
+            .sum()
+    }
+
+    /// Discrete energy, kinetic plus gradient, summed over the advanced points
+    /// with weight `delta_x^N`, halved once for every Neumann face a point is on.
+    ///
+    /// The gradient part is that of `w = u - g`, with `g` the value shared by
+    /// the Dirichlet faces ([`Boundaries::dirichlet_value`]). `L` applied to `u`
+    /// with the face values in its ghosts is the homogeneous `L` applied to `w`,
+    /// so for the two-level schemes this is
+    /// `½ |(x_1 - x_2) / dt|^2 - ½ <x_1 - g, L x_2>`, which leapfrog conserves to
+    /// rounding error with Dirichlet, Neumann and periodic faces. Dirichlet
+    /// faces with different values do work on the field; the energy then takes
+    /// `g = 0` and is not conserved. One-step integrators use
+    /// `½ |v|^2 - ½ <x_1 - g, L x_1>` and the
+    /// spectral solver the energy of its Fourier modes. Absorbing faces and the
+    /// PML let energy leave the domain.
+    pub fn energy(&self) -> f64 {
+        let volume = self.delta_x.powi(self.ndim as i32);
+        if let Some(spectral) = &self.spectral {
+            return spectral.energy() * volume;
+        }
+        let one_step = self.integrator.is_one_step();
+        let (x_1, x_2) = (&self.x_1, &self.x_2);
+        let lagged = if one_step { x_1 } else { x_2 };
+        let neumann: Vec<[bool; 2]> = self
+            .boundaries
+            .faces
+            .iter()
+            .map(|faces| faces.map(|c| c == BoundaryCondition::Neumann))
+            .collect();
+        let any_neumann = neumann.iter().flatten().any(|&n| n);
+        let mut idx = vec![0; self.ndim];
+        let scale = (self.delta_x * self.delta_x).recip();
+        let lift = self.boundaries.dirichlet_value().unwrap_or(0.0);
+        let mut sum = 0.0;
+        for_each_laplacian(lagged, &self.boundaries, self.stencil, |p, lap| {
+            let rate = if one_step {
+                self.v[p]
+            } else {
+                (x_1[p] - x_2[p]) / self.delta_t
+            };
+            let mut weight = 1.0;
+            if any_neumann {
+                x_1.unravel(p, &mut idx);
+                for (&i, (n, [lower, upper])) in idx.iter().zip(x_1.shape.iter().zip(&neumann)) {
+                    if (i == 0 && *lower) || (i == n - 1 && *upper) {
+                        weight *= 0.5;
+                    }
+                }
+            }
+            sum += weight * 0.5 * (rate * rate - (x_1[p] - lift) * lap * scale);
+        });
+        sum * volume
+    }
+}
+
+/// Calls `f(p, lap)` for every point the scheme advances, i.e. every point
+/// whose value is not imposed by `boundaries`, with `lap` being `delta_x^2`
+/// times the discrete Laplacian of `u` at `p`.
+///
+/// Points closer to a face than the stencil radius read ghost values from
+/// `boundaries`; all others take the fast path.
+fn for_each_laplacian(
+    u: &Field,
+    boundaries: &Boundaries,
+    stencil: Stencil,
+    mut f: impl FnMut(usize, f64),
+) {
+    u.for_each_inner(stencil.radius(), |p| f(p, stencil.interior_sum(u, p)));
+    for_each_face_laplacian(u, boundaries, stencil, f);
+}
+
+/// Sets `out[p] = f(p, lap)` at the points visited by [`for_each_laplacian`].
+///
+/// The fast path runs in parallel over groups of [`Field::slabs_per_task`]
+/// slabs of the first axis. Every point is computed the same way whatever the
+/// split, so the result does not depend on the number of threads.
+fn update_laplacian(
+    u: &Field,
+    boundaries: &Boundaries,
+    stencil: Stencil,
+    out: &mut Field,
+    f: impl Fn(usize, f64) -> f64 + Sync,
+) {
+    let r = stencil.radius();
+    let slabs = u.slabs_per_task();
+    let chunk = slabs * u.strides[0].max(1);
+    out.data
+        .par_chunks_mut(chunk)
+        .enumerate()
+        .for_each(|(k, out)| {
+            let base = k * chunk;
+            u.for_each_inner_in(r, k * slabs..(k + 1) * slabs, |p| {
+                out[p - base] = f(p, stencil.interior_sum(u, p));
+            });
+        });
+    for_each_face_laplacian(u, boundaries, stencil, |p, lap| out[p] = f(p, lap));
+}
+
+/// The points of [`for_each_laplacian`] closer to a face than the stencil
+/// radius.
+fn for_each_face_laplacian(
+    u: &Field,
+    boundaries: &Boundaries,
+    stencil: Stencil,
+    mut f: impl FnMut(usize, f64),
+) {
+    let r = stencil.radius();
+    let ndim = u.ndim();
+    let near_face = |i: usize, n: usize| i < r || i + r >= n;
+    let mut idx = vec![0; ndim];
+    for axis in 0..ndim {
+        let n = u.shape[axis];
+        for i in (0..n).filter(|&i| near_face(i, n)) {
+            for p in u.face_points(axis, i) {
+                u.unravel(p, &mut idx);
+                // Points near several faces are visited from the first axis.
+                let seen = (0..axis).any(|b| near_face(idx[b], u.shape[b]));
+                if seen || boundaries.is_imposed(&idx, &u.shape) {
+                    continue;
+                }
+                let lap = (0..ndim)
+                    .map(|b| stencil.second_difference(u, boundaries, p, b, idx[b]))
+                    .sum();
+                f(p, lap);
+            }
+        }
+    }
+}
+
+/// Writes `scale * delta_x^2` times the discrete Laplacian of `u` into `out`
+/// at every advanced point, and the matching time derivative of the imposed
+/// values (zero, or the periodic copy) elsewhere.
+fn laplacian_into(
+    u: &Field,
+    boundaries: &Boundaries,
+    stencil: Stencil,
+    scale: f64,
+    out: &mut Field,
+) {
+    out.data.fill(0.0);
+    update_laplacian(u, boundaries, stencil, out, |_, lap| scale * lap);
+    boundaries.apply_rate(out);
+}
@@ -0,0 +1,164 @@
+//! Huygens' principle in numbers: what a radial pulse leaves behind its front.
+//!
+//! Initial data supported in `r <= a` reach a probe at radius `R` no later than
+//! `t = R - a` and, in odd dimensions `N >= 3`, have left it for good at
+//! `t = R + a`. In even dimensions the solution keeps a tail behind the front,
+//! and in 1D a nonzero initial velocity leaves a constant plateau.
+//! [`Huygens::measure`] follows `u(R, t)` with the radial solver past `R + a`
+//! and reports how much amplitude and energy remain.
+
+use crate::boundary::{AbsorbingOrder, BoundaryCondition};
+use crate::error::{Error, Result};
+use crate::initial::InitialCondition;
+use crate::radial::RadialGrid;
+
+/// Fraction of their peak below which the initial data count as zero when
+/// locating their support.
+pub const SUPPORT_TOLERANCE: f64 = 1e-6;
+
+/// What one dimension leaves behind the front at the probe.
+#[derive(Debug, Clone)]
+pub struct Tail {
+    pub dim: f64,
+    /// `R + a`, after which the front has passed the probe.
+    pub front_passed: f64,
+    /// Largest `|u(R, t)|` over the whole run.
+    pub peak: f64,
+    /// Largest `|u(R, t)|` after `front_passed`.
+    pub residual: f64,
+    /// Fraction of the initial energy inside the probe sphere at the end.
+    pub tail_energy: f64,
+    /// `(t, u(R, t))` after every step.
+    pub signal: Vec<(f64, f64)>,
+}
+
+/// Largest radius in `[0, extent]` at which either profile exceeds
+/// [`SUPPORT_TOLERANCE`] times their common peak.
+pub fn support_radius(
+    displacement: &InitialCondition,
+    velocity: &InitialCondition,
+    extent: f64,
+) -> f64 {
+    const SAMPLES: usize = 10_000;
+    let values: Vec<f64> = (0..=SAMPLES)
+        .map(|i| {
+            let r = [extent * i as f64 / SAMPLES as f64];
+            displacement
+                .evaluate(&r)
+                .abs()
+                .max(velocity.evaluate(&r).abs())
+        })
+        .collect();
+    let peak = values.iter().copied().fold(0.0, f64::max);
+    let last = values
+        .iter()
+        .rposition(|&v| v > SUPPORT_TOLERANCE * peak)
+        .unwrap_or(0);
+    extent * last as f64 / SAMPLES as f64
+}
+
+/// Radial initial data and the radial grids that carry them, shared by the
+/// [`Huygens`] and [`Decay`](crate::decay::Decay) reports.
+#[derive(Debug, Clone)]
+pub struct RadialPulse {
+    pub displacement: InitialCondition,
+    pub velocity: InitialCondition,
+    /// Radius `a` of the initial data, see [`support_radius`].
+    pub support: f64,
+    /// Points of the radial grid.
+    pub points: usize,
+    /// Courant number of the radial grid.
+    pub courant: f64,
+}
+
+impl RadialPulse {
+    /// The pulse of `displacement` and `velocity`, with its support searched
+    /// within `extent` of the origin.
+    pub fn new(
+        displacement: InitialCondition,
+        velocity: InitialCondition,
+        extent: f64,
+        points: usize,
+        courant: f64,
+    ) -> Self {
+        RadialPulse {
+            support: support_radius(&displacement, &velocity, extent),
+            displacement,
+            velocity,
+            points,
+            courant,
+        }
+    }
+
+    /// Grid of dimension `dim` on `[0, radius]`, with an absorbing outer face,
+    /// holding the pulse at `t = 0`.
+    pub fn grid(&self, dim: f64, radius: f64) -> Result<RadialGrid> {
+        let mut grid = RadialGrid::new(dim, self.points, radius);
+        grid.boundary = BoundaryCondition::Absorbing {
+            order: AbsorbingOrder::default(),
+        };
+        grid.validate()?;
+        grid.set_courant(self.courant)?;
+        grid.initialize_from(&self.displacement, &self.velocity);
+        Ok(grid)
+    }
+}
+
+/// Probe setup shared by every dimension of a report.
+#[derive(Debug, Clone)]
+pub struct Huygens {
+    pub pulse: RadialPulse,
+    /// Probe radius `R`.
+    pub probe: f64,
+    /// Time the probe keeps recording after the front has passed.
+    pub window: f64,
+}
+
+impl Huygens {
+    /// Checks that the data are radial and the probe and window positive.
+    pub fn validate(&self) -> Result<()> {
+        let pulse = &self.pulse;
+        InitialCondition::check_radial(&pulse.displacement, &pulse.velocity, "the huygens report")?;
+        if !(self.probe > 0.0 && self.window > 0.0) {
+            return Err(Error::InvalidConfig(format!(
+                "probe radius and window must be positive, got {} and {}",
+                self.probe, self.window
+            )));
+        }
+        Ok(())
+    }
+
+    /// Runs the radial solver in dimension `dim` until `window` after the front
+    /// has passed the probe.
+    ///
+    /// The solver's outer radius `R + a + window` keeps waves reflected there
+    /// from reaching the probe in time; its absorbing condition weakens them
+    /// anyway.
+    pub fn measure(&self, dim: f64) -> Result<Tail> {
+        self.validate()?;
+        let front_passed = self.probe + self.pulse.support;
+        let until = front_passed + self.window;
+        let mut grid = self.pulse.grid(dim, until)?;
+        let initial = grid.energy();
+
+        let mut signal = vec![(grid.time, grid.value_at(self.probe))];
+        while grid.time < until {
+            grid.step();
+            signal.push((grid.time, grid.value_at(self.probe)));
+        }
+        let peak = signal.iter().map(|(_, u)| u.abs()).fold(0.0, f64::max);
+        let residual = signal
+            .iter()
+            .filter(|(t, _)| *t >= front_passed)
+            .map(|(_, u)| u.abs())
+            .fold(0.0, f64::max);
+        Ok(Tail {
+            dim,
+            front_passed,
+            peak,
+            residual,
+            tail_energy: grid.energy_within(self.probe) / initial,
+            signal,
+        })
+    }
+}
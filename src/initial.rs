@@ -0,0 +1,258 @@
+//! Initial displacement and velocity profiles.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// User-supplied profile for [`InitialCondition::Custom`].
+pub type Profile = Arc<dyn Fn(&[f64]) -> f64 + Send + Sync>;
+
+/// A scalar profile `f(x)` used as initial displacement or velocity.
+///
+/// `center` is the origin when left empty. Every variant except `Custom`
+/// can be read from a configuration file, tagged by `type`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum InitialCondition {
+    /// Identically zero.
+    Zero,
+    /// `amplitude * exp(-|x - center|^2 / width^2)`.
+    Gaussian {
+        amplitude: f64,
+        width: f64,
+        #[serde(default)]
+        center: Vec<f64>,
+    },
+    /// Smooth bump `amplitude * exp(1 - 1 / (1 - s^2))`, `s = |x - center| / radius`,
+    /// vanishing identically for `s >= 1`.
+    Bump {
+        amplitude: f64,
+        radius: f64,
+        #[serde(default)]
+        center: Vec<f64>,
+    },
+    /// `amplitude * cos(wave_vector . x + phase)`.
+    PlaneWave {
+        amplitude: f64,
+        wave_vector: Vec<f64>,
+        #[serde(default)]
+        phase: f64,
+    },
+    /// Gaussian ring (2D) or shell: `amplitude * exp(-(|x - center| - radius)^2 / width^2)`.
+    Shell {
+        amplitude: f64,
+        radius: f64,
+        width: f64,
+        #[serde(default)]
+        center: Vec<f64>,
+    },
+    /// Sum of several profiles, e.g. multiple sources.
+    Sum { terms: Vec<InitialCondition> },
+    /// Arbitrary user function of the position.
+    #[serde(skip)]
+    Custom(Profile),
+}
+
+impl Default for InitialCondition {
+    /// The README pulse `exp(-40 r^2)`.
+    fn default() -> Self {
+        InitialCondition::Gaussian {
+            amplitude: 1.0,
+            width: 40f64.sqrt().recip(),
+            center: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Debug for InitialCondition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InitialCondition::Zero => write!(f, "Zero"),
+            InitialCondition::Gaussian {
+                amplitude,
+                width,
+                center,
+            } => f
+                .debug_struct("Gaussian")
+                .field("amplitude", amplitude)
+                .field("width", width)
+                .field("center", center)
+                .finish(),
+            InitialCondition::Bump {
+                amplitude,
+                radius,
+                center,
+            } => f
+                .debug_struct("Bump")
+                .field("amplitude", amplitude)
+                .field("radius", radius)
+                .field("center", center)
+                .finish(),
+            InitialCondition::PlaneWave {
+                amplitude,
+                wave_vector,
+                phase,
+            } => f
+                .debug_struct("PlaneWave")
+                .field("amplitude", amplitude)
+                .field("wave_vector", wave_vector)
+                .field("phase", phase)
+                .finish(),
+            InitialCondition::Shell {
+                amplitude,
+                radius,
+                width,
+                center,
+            } => f
+                .debug_struct("Shell")
+                .field("amplitude", amplitude)
+                .field("radius", radius)
+                .field("width", width)
+                .field("center", center)
+                .finish(),
+            InitialCondition::Sum { terms } => f.debug_struct("Sum").field("terms", terms).finish(),
+            InitialCondition::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+impl InitialCondition {
+    pub fn custom(f: impl Fn(&[f64]) -> f64 + Send + Sync + 'static) -> Self {
+        InitialCondition::Custom(Arc::new(f))
+    }
+
+    pub fn evaluate(&self, x: &[f64]) -> f64 {
+        match self {
+            InitialCondition::Zero => 0.0,
+            InitialCondition::Gaussian {
+                amplitude,
+                width,
+                center,
+            } => amplitude * (-distance(x, center).powi(2) / (width * width)).exp(),
+            InitialCondition::Bump {
+                amplitude,
+                radius,
+                center,
+            } => {
+                let s2 = (distance(x, center) / radius).powi(2);
+                if s2 < 1.0 {
+                    amplitude * (1.0 - 1.0 / (1.0 - s2)).exp()
+                } else {
+                    0.0
+                }
+            }
+            InitialCondition::PlaneWave {
+                amplitude,
+                wave_vector,
+                phase,
+            } => {
+                let kx: f64 = x.iter().zip(wave_vector.iter()).map(|(x, k)| x * k).sum();
+                amplitude * (kx + phase).cos()
+            }
+            InitialCondition::Shell {
+                amplitude,
+                radius,
+                width,
+                center,
+            } => amplitude * (-((distance(x, center) - radius) / width).powi(2)).exp(),
+            InitialCondition::Sum { terms } => terms.iter().map(|t| t.evaluate(x)).sum(),
+            InitialCondition::Custom(f) => f(x),
+        }
+    }
+
+    /// Checks parameters and that every vector has `dim` components.
+    pub fn validate(&self, dim: usize) -> Result<()> {
+        let invalid = |msg: String| Err(Error::InvalidConfig(msg));
+        let check_center = |name: &str, center: &[f64]| {
+            if !center.is_empty() && center.len() != dim {
+                return invalid(format!(
+                    "{} center has {} coordinates, expected {}",
+                    name,
+                    center.len(),
+                    dim
+                ));
+            }
+            Ok(())
+        };
+        let check_positive = |name: &str, value: f64| {
+            if !value.is_finite() || value <= 0.0 {
+                return invalid(format!("{} must be positive, got {}", name, value));
+            }
+            Ok(())
+        };
+        match self {
+            InitialCondition::Zero | InitialCondition::Custom(_) => Ok(()),
+            InitialCondition::Gaussian { width, center, .. } => {
+                check_positive("gaussian width", *width)?;
+                check_center("gaussian", center)
+            }
+            InitialCondition::Bump { radius, center, .. } => {
+                check_positive("bump radius", *radius)?;
+                check_center("bump", center)
+            }
+            InitialCondition::PlaneWave { wave_vector, .. } => {
+                if wave_vector.len() != dim {
+                    return invalid(format!(
+                        "plane wave vector has {} components, expected {}",
+                        wave_vector.len(),
+                        dim
+                    ));
+                }
+                Ok(())
+            }
+            InitialCondition::Shell {
+                radius,
+                width,
+                center,
+                ..
+            } => {
+                if !radius.is_finite() || *radius < 0.0 {
+                    return invalid(format!("shell radius must be non-negative, got {}", radius));
+                }
+                check_positive("shell width", *width)?;
+                check_center("shell", center)
+            }
+            InitialCondition::Sum { terms } => terms.iter().try_for_each(|t| t.validate(dim)),
+        }
+    }
+
+    /// Fails unless `displacement` and `velocity` are both radial, which `user`
+    /// (e.g. "the radial solver") needs.
+    pub fn check_radial(
+        displacement: &InitialCondition,
+        velocity: &InitialCondition,
+        user: &str,
+    ) -> Result<()> {
+        if !(displacement.is_radial() && velocity.is_radial()) {
+            return Err(Error::InvalidConfig(format!(
+                "{} needs radial initial data centred at the origin",
+                user
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the profile depends on `|x|` only, so that evaluating it at the
+    /// one-dimensional point `[r]` gives the whole field. `Custom` profiles are
+    /// taken at their word.
+    pub fn is_radial(&self) -> bool {
+        match self {
+            InitialCondition::Zero | InitialCondition::Custom(_) => true,
+            InitialCondition::Gaussian { center, .. }
+            | InitialCondition::Bump { center, .. }
+            | InitialCondition::Shell { center, .. } => center.iter().all(|&c| c == 0.0),
+            InitialCondition::PlaneWave { .. } => false,
+            InitialCondition::Sum { terms } => terms.iter().all(InitialCondition::is_radial),
+        }
+    }
+}
+
+/// Euclidean distance from `x` to `center`, which is the origin when empty.
+fn distance(x: &[f64], center: &[f64]) -> f64 {
+    x.iter()
+        .enumerate()
+        .map(|(a, x)| (x - center.get(a).unwrap_or(&0.0)).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
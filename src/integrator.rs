@@ -0,0 +1,98 @@
+//! Time integrators for `u_tt = Δu`.
+
+use crate::boundary::{Boundaries, BoundaryCondition};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Scheme advancing the semi-discrete system in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeIntegrator {
+    /// Three-level `u^{n+1} = 2u^n - u^{n-1} + dt^2 Δu^n`, second order.
+    #[default]
+    Leapfrog,
+    /// Velocity Verlet on the first-order system `u_t = v, v_t = Δu`; the
+    /// same positions as leapfrog, with a velocity at every step.
+    Verlet,
+    /// Classical fourth-order Runge–Kutta on `u_t = v, v_t = Δu`.
+    Rk4,
+    /// Leapfrog with the modified-equation (Lax–Wendroff) correction
+    /// `dt^4 / 12 Δ^2 u^n`, fourth order in time.
+    ModifiedEquation,
+}
+
+impl TimeIntegrator {
+    pub const ALL: [TimeIntegrator; 4] = [
+        TimeIntegrator::Leapfrog,
+        TimeIntegrator::Verlet,
+        TimeIntegrator::Rk4,
+        TimeIntegrator::ModifiedEquation,
+    ];
+
+    /// Stable time step relative to leapfrog with the same Laplacian: the
+    /// largest `dt ω / 2` that keeps every mode of frequency `ω` bounded.
+    pub fn stability_factor(self) -> f64 {
+        match self {
+            TimeIntegrator::Leapfrog | TimeIntegrator::Verlet => 1.0,
+            // RK4 reaches 2 sqrt(2) along the imaginary axis.
+            TimeIntegrator::Rk4 => 2f64.sqrt(),
+            // |2 - z + z^2 / 12| <= 2 for z = (dt ω)^2 <= 12.
+            TimeIntegrator::ModifiedEquation => 3f64.sqrt(),
+        }
+    }
+
+    /// Whether the scheme carries the velocity `u_t` instead of `u^{n-1}`.
+    pub fn is_one_step(self) -> bool {
+        matches!(self, TimeIntegrator::Verlet | TimeIntegrator::Rk4)
+    }
+
+    /// Absorbing faces and the perfectly matched layer are formulated for
+    /// leapfrog only.
+    pub fn check_compatible(self, boundaries: &Boundaries, pml: bool) -> Result<()> {
+        if self == TimeIntegrator::Leapfrog {
+            return Ok(());
+        }
+        let absorbing = boundaries
+            .faces
+            .iter()
+            .flatten()
+            .any(|c| matches!(c, BoundaryCondition::Absorbing { .. }));
+        if absorbing || pml {
+            return Err(Error::InvalidConfig(format!(
+                "absorbing faces and the pml require the leapfrog integrator, not {}",
+                self
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TimeIntegrator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TimeIntegrator::Leapfrog => "leapfrog",
+            TimeIntegrator::Verlet => "verlet",
+            TimeIntegrator::Rk4 => "rk4",
+            TimeIntegrator::ModifiedEquation => "modified_equation",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for TimeIntegrator {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        TimeIntegrator::ALL
+            .into_iter()
+            .find(|integrator| integrator.to_string() == s)
+            .ok_or_else(|| {
+                Error::InvalidConfig(format!(
+                    "unknown integrator {:?}, expected leapfrog, verlet, rk4 or modified_equation",
+                    s
+                ))
+            })
+    }
+}
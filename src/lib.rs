@@ -0,0 +1,36 @@
+//! Finite-difference solvers for the wave equation `u_tt = Δu` on the
+//! N-dimensional cube, used to compare wave propagation in odd and even
+//! dimensions.
+
+pub mod analytic;
+pub mod boundary;
+pub mod config;
+pub mod decay;
+pub mod error;
+pub mod field;
+pub mod grid;
+pub mod huygens;
+pub mod initial;
+pub mod integrator;
+pub mod pml;
+pub mod profile;
+pub mod radial;
+pub mod render;
+pub mod spectral;
+pub mod stencil;
+
+pub use analytic::{Analytic, ErrorNorms, RadialProfile};
+pub use boundary::{AbsorbingOrder, Boundaries, BoundaryCondition, Side};
+pub use config::SimulationConfig;
+pub use decay::{Decay, DecayFit, PowerLaw};
+pub use error::{Error, Result};
+pub use field::Field;
+pub use grid::GridND;
+pub use huygens::{Huygens, RadialPulse, Tail};
+pub use initial::InitialCondition;
+pub use integrator::TimeIntegrator;
+pub use pml::{Pml, PmlConfig};
+pub use profile::{radial_profile, RadialBin};
+pub use radial::RadialGrid;
+pub use spectral::{Solver, Spectral};
+pub use stencil::Stencil;
@@ -1,1000 +1,919 @@
-use apng::{load_dynamic_image, Encoder, Frame, PNGImage};
-use plotters::prelude::*;
-use std::fs::File;
-use std::io::{BufWriter, Read};
-use std::path::Path;
-
-#[derive(Debug)]
-pub struct Grid4D {
-    pub delta_t: f64,
-    pub delta_x: f64,
-    pub num_grid: usize,
-    pub x_1: Vec<Vec<Vec<Vec<f64>>>>,
-    pub x_2: Vec<Vec<Vec<Vec<f64>>>>,
-    pub tmp: Vec<Vec<Vec<Vec<f64>>>>,
+use clap::{Args, Parser, Subcommand};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use wave_equation_difference_between_3d_and_4d::{
+    radial_profile, render, Analytic, Decay, Error, ErrorNorms, GridND, Huygens, InitialCondition,
+    PmlConfig, RadialGrid, RadialPulse, Result, SimulationConfig, Solver, Stencil, TimeIntegrator,
+};
+
+#[derive(Parser)]
+#[command(about = "Wave equation in N dimensions: odd vs. even dimensional propagation")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
 }
 
-impl Grid4D {
-    pub fn new(dim: usize) -> Self {
-        Grid4D {
-            delta_t: (2.0 / (dim as f64 - 1.0)).powi(2),
-            delta_x: 2.0 / (dim as f64 - 1.0),
-            num_grid: dim,
-            x_1: vec![vec![vec![vec![0.0; dim]; dim]; dim]; dim],
-            x_2: vec![vec![vec![vec![0.0; dim]; dim]; dim]; dim],
-            tmp: vec![vec![vec![vec![0.0; dim]; dim]; dim]; dim],
-        }
+#[derive(Subcommand)]
+enum Command {
+    /// Simulate one dimension and write its frames and animation
+    Run {
+        /// Spatial dimension N [default: 4]
+        #[arg(long)]
+        dim: Option<usize>,
+        #[command(flatten)]
+        sim: SimArgs,
+    },
+    /// Simulate several dimensions side by side on one chart
+    Compare {
+        /// Spatial dimensions to compare
+        #[arg(long, value_delimiter = ',', default_value = "1,2,3,4")]
+        dims: Vec<usize>,
+        #[command(flatten)]
+        sim: SimArgs,
+    },
+    /// Solve the radially symmetric problem u_tt = u_rr + (N-1)/r u_r and write u(r)
+    Radial {
+        /// Dimension N, which may be non-integer [default: the configuration's dim]
+        #[arg(long)]
+        dim: Option<f64>,
+        /// Radial grid points on [0, R], R the upper end of the domain
+        #[arg(long, default_value_t = 10_001)]
+        points: usize,
+        /// Time at which u(r) is written
+        #[arg(long, default_value_t = 1.0)]
+        until: f64,
+        /// Also run the full grid of the configuration and report its deviation along the first axis
+        #[arg(long)]
+        check: bool,
+        #[command(flatten)]
+        sim: SimArgs,
+    },
+    /// Measure the error and observed order of accuracy over a sequence of grid sizes
+    Convergence {
+        /// Spatial dimension N [default: the configuration's dim]
+        #[arg(long)]
+        dim: Option<usize>,
+        /// Grid sizes, coarsest first
+        #[arg(long, value_delimiter = ',', default_value = "17,33,65,129")]
+        sizes: Vec<usize>,
+        /// Times at which the errors are measured
+        #[arg(long, value_delimiter = ',', default_value = "0.25,0.5")]
+        times: Vec<f64>,
+        /// Compare with the largest size instead of the analytic free-space solution
+        #[arg(long)]
+        finest: bool,
+        #[command(flatten)]
+        sim: SimArgs,
+    },
+    /// Measure the amplitude and energy a radial pulse leaves behind its front, per dimension
+    Huygens {
+        /// Dimensions to compare, possibly non-integer
+        #[arg(long, value_delimiter = ',', default_value = "1,2,3,4,5,6")]
+        dims: Vec<f64>,
+        /// Probe radius R
+        #[arg(long, default_value_t = 0.5)]
+        radius: f64,
+        /// Time the probe keeps recording after the front has passed
+        #[arg(long, default_value_t = 1.0)]
+        window: f64,
+        /// Radial grid points
+        #[arg(long, default_value_t = 4001)]
+        points: usize,
+        #[command(flatten)]
+        sim: SimArgs,
+    },
+    /// Fit the decay of the outgoing front's peak to a power law, per dimension
+    Decay {
+        /// Dimensions to compare, possibly non-integer
+        #[arg(long, value_delimiter = ',', default_value = "1,2,3,4,5,6")]
+        dims: Vec<f64>,
+        /// First time included in the fit
+        #[arg(long, default_value_t = 2.0)]
+        start: f64,
+        /// Last time included in the fit, where the run stops
+        #[arg(long, default_value_t = 8.0)]
+        until: f64,
+        /// Radial grid points
+        #[arg(long, default_value_t = 8001)]
+        points: usize,
+        #[command(flatten)]
+        sim: SimArgs,
+    },
+    /// Assemble existing frames into an animated PNG
+    Render {
+        /// Number of frames to assemble
+        #[arg(long, default_value_t = 1200)]
+        frames: usize,
+        /// Directory containing 0000.png, 0001.png, ...
+        #[arg(long, default_value = ".")]
+        input_dir: PathBuf,
+        /// Animated PNG to write
+        #[arg(long, default_value = "wave.png")]
+        output: PathBuf,
+    },
+}
+
+#[derive(Args)]
+struct SimArgs {
+    /// TOML or JSON configuration file; the flags below override its values
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Grid points per axis [default: 65]
+    #[arg(long)]
+    size: Option<usize>,
+    /// Use the dispersion-free spectral solver; every face must be periodic
+    #[arg(long)]
+    spectral: bool,
+    /// Order of the Laplacian: second, fourth, sixth or eighth (or 2, 4, 6, 8) [default: second]
+    #[arg(long)]
+    stencil: Option<Stencil>,
+    /// Time integrator: leapfrog, verlet, rk4 or modified_equation [default: leapfrog]
+    #[arg(long)]
+    integrator: Option<TimeIntegrator>,
+    /// Courant number delta_t / (CFL limit), at most 1 [default: 0.5]
+    #[arg(long)]
+    courant: Option<f64>,
+    /// Explicit time step; rejected if it violates the CFL condition
+    #[arg(long, conflicts_with = "courant")]
+    delta_t: Option<f64>,
+    /// Thickness in grid points of a perfectly matched layer inside every face
+    #[arg(long)]
+    pml: Option<usize>,
+    /// Worker threads for the stencil update [default: all cores]
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Number of frames to draw [default: 1200]
+    #[arg(long)]
+    frames: Option<usize>,
+    /// Time steps between frames [default: 10]
+    #[arg(long)]
+    interval: Option<usize>,
+    /// Directory for frames, the animation and the echoed configuration [default: .]
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+    /// Write the discrete energy after every step to energy.csv
+    #[arg(long)]
+    energy: bool,
+    /// Abort once the energy changes in one step by more than this fraction of its initial value
+    #[arg(long)]
+    max_energy_drift: Option<f64>,
+    /// Record u at this point after every step into probes.csv; repeat for several probes
+    #[arg(long = "probe", value_name = "X,Y,...", value_parser = parse_point)]
+    probes: Vec<Vec<f64>>,
+    /// Bin every frame by radius into profile.csv and plot u(r) and r^((N-1)/2) u(r)
+    #[arg(long)]
+    radial_profile: bool,
+}
+
+/// Comma-separated coordinates, e.g. `0.5,0,0`.
+fn parse_point(s: &str) -> std::result::Result<Vec<f64>, String> {
+    s.split(',')
+        .map(|x| {
+            x.trim()
+                .parse()
+                .map_err(|_| format!("invalid coordinate {:?}", x))
+        })
+        .collect()
+}
+
+impl SimArgs {
+    /// Loads the configuration file, if any, applies the command-line overrides, validates
+    /// and sizes the thread pool.
+    fn resolve(&self, dim: Option<usize>) -> Result<SimulationConfig> {
+        let config = self.configure(dim)?;
+        config.validate()?;
+        config.apply_threads()?;
+        Ok(config)
     }
 
-    pub fn initialize(&mut self) {
-        // x in [-1, 1]
-        for i in 0..self.num_grid {
-            let x = -1.0 + 2.0 * i as f64 / (self.num_grid as f64 - 1.0);
-            for j in 0..self.num_grid {
-                let y = -1.0 + 2.0 * j as f64 / (self.num_grid as f64 - 1.0);
-                for k in 0..self.num_grid {
-                    let z = -1.0 + 2.0 * k as f64 / (self.num_grid as f64 - 1.0);
-                    for l in 0..self.num_grid {
-                        let w = -1.0 + 2.0 * l as f64 / (self.num_grid as f64 - 1.0);
-                        self.x_1[i][j][k][l] = (-40.0 * (x * x + y * y + z * z + w * w)).exp();
-                        self.x_2[i][j][k][l] = (-40.0 * (x * x + y * y + z * z + w * w)).exp();
-                    }
-                }
-            }
+    /// Loads the configuration file, if any, and applies the command-line overrides
+    /// without validating the result.
+    fn configure(&self, dim: Option<usize>) -> Result<SimulationConfig> {
+        let mut config = match &self.config {
+            Some(path) => SimulationConfig::load(path)?,
+            None => SimulationConfig::default(),
+        };
+        if let Some(dim) = dim {
+            config.dim = dim;
         }
-        for i in 0..self.num_grid {
-            self.x_1[i][0][0][0] = 0.0;
-            self.x_1[self.num_grid - 1][0][0][0] = 0.0;
-            self.x_1[0][i][0][0] = 0.0;
-            self.x_1[0][self.num_grid - 1][0][0] = 0.0;
-            self.x_1[0][0][i][0] = 0.0;
-            self.x_1[0][0][self.num_grid - 1][0] = 0.0;
-            self.x_1[0][0][0][i] = 0.0;
-            self.x_1[0][0][0][self.num_grid - 1] = 0.0;
+        if let Some(size) = self.size {
+            config.size = size;
         }
-    }
-
-    pub fn step(&mut self) {
-        for i in 1..(self.num_grid - 1) {
-            for j in 1..(self.num_grid - 1) {
-                for k in 1..(self.num_grid - 1) {
-                    for l in 1..(self.num_grid - 1) {
-                        self.tmp[i][j][k][l] = 2.0 * self.x_1[i][j][k][l] - self.x_2[i][j][k][l]
-                            + self.delta_t * self.delta_t / (self.delta_x * self.delta_x)
-                                * (self.x_1[i + 1][j][k][l] - 2.0 * self.x_1[i][j][k][l]
-                                    + self.x_1[i - 1][j][k][l]
-                                    + self.x_1[i][j + 1][k][l]
-                                    - 2.0 * self.x_1[i][j][k][l]
-                                    + self.x_1[i][j - 1][k][l]
-                                    + self.x_1[i][j][k + 1][l]
-                                    - 2.0 * self.x_1[i][j][k][l]
-                                    + self.x_1[i][j][k - 1][l]
-                                    + self.x_1[i][j][k][l + 1]
-                                    - 2.0 * self.x_1[i][j][k][l]
-                                    + self.x_1[i][j][k][l - 1]);
-                    }
-                }
-            }
+        if self.spectral {
+            config.solver = Solver::Spectral;
         }
-        for i in 1..(self.num_grid - 1) {
-            for j in 1..(self.num_grid - 1) {
-                for k in 1..(self.num_grid - 1) {
-                    for l in 1..(self.num_grid - 1) {
-                        self.x_2[i][j][k][l] = self.x_1[i][j][k][l];
-                        self.x_1[i][j][k][l] = self.tmp[i][j][k][l];
-                    }
-                }
-            }
+        if let Some(stencil) = self.stencil {
+            config.stencil = stencil;
         }
-    }
-
-    pub fn draw(&self, i: usize) {
-        let out_file_name = format!("{:04}", i).to_string() + ".png";
-
-        let root_area = BitMapBackend::new(&out_file_name, (2560, 1440)).into_drawing_area();
-
-        root_area.fill(&WHITE).unwrap();
-
-        let root_area = root_area
-            .titled("4D, u_tt = u_xx + u_yy + u_zz + u_ww.", ("sans-serif", 100))
-            .unwrap();
-
-        let (upper, lower) = root_area.split_vertically(720);
-
-        let drawing_areas = upper.split_evenly((1, 2));
-
-        let mut cc0 = ChartBuilder::on(&drawing_areas[0])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("x",), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc0.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc0.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[i][self.num_grid / 2][self.num_grid / 2][self.num_grid / 2]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let mut cc1 = ChartBuilder::on(&drawing_areas[1])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("y",), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc1.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc1.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[self.num_grid / 2][i][self.num_grid / 2][self.num_grid / 2]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let drawing_areas = lower.split_evenly((1, 2));
-
-        let mut cc2 = ChartBuilder::on(&drawing_areas[0])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("z"), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc2.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc2.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[self.num_grid / 2][self.num_grid / 2][i][self.num_grid / 2]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let mut cc3 = ChartBuilder::on(&drawing_areas[1])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("w"), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc3.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc3.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[self.num_grid / 2][self.num_grid / 2][self.num_grid / 2][i]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-    }
-
-    pub fn gen_apng(&self, num: usize) {
-        let mut files = vec![];
-
-        for i in 0..num {
-            files.push(format!("{:04}", i).to_string() + ".png");
+        if let Some(integrator) = self.integrator {
+            config.integrator = integrator;
         }
-
-        let mut png_images: Vec<PNGImage> = Vec::new();
-
-        for f in files.iter() {
-            let mut file = File::open(f).unwrap();
-            let mut buffer = vec![];
-            file.read_to_end(&mut buffer).unwrap();
-            let img = image::load_from_memory(&buffer).unwrap();
-            png_images.push(load_dynamic_image(img).unwrap());
+        if let Some(courant) = self.courant {
+            config.courant = courant;
+            config.delta_t = None;
         }
-
-        let path = Path::new(r"wave_4d.png");
-        let mut out = BufWriter::new(File::create(path).unwrap());
-
-        let config = apng::create_config(&png_images, None).unwrap();
-        let mut encoder = Encoder::new(&mut out, config).unwrap();
-
-        for image in png_images.iter() {
-            let frame = Frame {
-                delay_num: Some(1),
-                delay_den: Some(20),
-                ..Default::default()
-            };
-            encoder.write_frame(image, frame).unwrap();
+        if let Some(delta_t) = self.delta_t {
+            config.delta_t = Some(delta_t);
         }
-
-        match encoder.finish_encode() {
-            Ok(_n) => println!("success"),
-            Err(err) => eprintln!("{}", err),
+        if let Some(thickness) = self.pml {
+            let pml = config.pml.get_or_insert_with(PmlConfig::default);
+            pml.thickness = thickness;
         }
-    }
-}
-
-#[derive(Debug)]
-pub struct Grid3D {
-    pub delta_t: f64,
-    pub delta_x: f64,
-    pub num_grid: usize,
-    pub x_1: Vec<Vec<Vec<f64>>>,
-    pub x_2: Vec<Vec<Vec<f64>>>,
-    pub tmp: Vec<Vec<Vec<f64>>>,
-}
-
-impl Grid3D {
-    pub fn new(dim: usize) -> Self {
-        Grid3D {
-            delta_t: (2.0 / (dim as f64 - 1.0)).powi(2),
-            delta_x: 2.0 / (dim as f64 - 1.0),
-            num_grid: dim,
-            x_1: vec![vec![vec![0.0; dim]; dim]; dim],
-            x_2: vec![vec![vec![0.0; dim]; dim]; dim],
-            tmp: vec![vec![vec![0.0; dim]; dim]; dim],
+        if let Some(threads) = self.threads {
+            config.threads = Some(threads);
         }
-    }
-
-    pub fn initialize(&mut self) {
-        // x in [-1, 1]
-        for i in 0..self.num_grid {
-            let x = -1.0 + 2.0 * i as f64 / (self.num_grid as f64 - 1.0);
-            for j in 0..self.num_grid {
-                let y = -1.0 + 2.0 * j as f64 / (self.num_grid as f64 - 1.0);
-                for k in 0..self.num_grid {
-                    let z = -1.0 + 2.0 * k as f64 / (self.num_grid as f64 - 1.0);
-                    self.x_1[i][j][k] = (-40.0 * (x * x + y * y + z * z)).exp();
-                    self.x_2[i][j][k] = (-40.0 * (x * x + y * y + z * z)).exp();
-                }
-            }
+        if let Some(frames) = self.frames {
+            config.output.frames = frames;
         }
-        for i in 0..self.num_grid {
-            self.x_1[i][0][0] = 0.0;
-            self.x_1[self.num_grid - 1][0][0] = 0.0;
-            self.x_1[0][i][0] = 0.0;
-            self.x_1[0][self.num_grid - 1][0] = 0.0;
-            self.x_1[0][0][i] = 0.0;
-            self.x_1[0][0][self.num_grid - 1] = 0.0;
+        if let Some(interval) = self.interval {
+            config.output.interval = interval;
         }
-    }
-
-    pub fn step(&mut self) {
-        for i in 1..(self.num_grid - 1) {
-            for j in 1..(self.num_grid - 1) {
-                for k in 1..(self.num_grid - 1) {
-                    self.tmp[i][j][k] = 2.0 * self.x_1[i][j][k] - self.x_2[i][j][k]
-                        + self.delta_t * self.delta_t / (self.delta_x * self.delta_x)
-                            * (self.x_1[i + 1][j][k] - 2.0 * self.x_1[i][j][k]
-                                + self.x_1[i - 1][j][k]
-                                + self.x_1[i][j + 1][k]
-                                - 2.0 * self.x_1[i][j][k]
-                                + self.x_1[i][j - 1][k]
-                                + self.x_1[i][j][k + 1]
-                                - 2.0 * self.x_1[i][j][k]
-                                + self.x_1[i][j][k - 1]);
-                }
-            }
+        if let Some(dir) = &self.output_dir {
+            config.output.dir = dir.clone();
         }
-        for i in 1..(self.num_grid - 1) {
-            for j in 1..(self.num_grid - 1) {
-                for k in 1..(self.num_grid - 1) {
-                    self.x_2[i][j][k] = self.x_1[i][j][k];
-                    self.x_1[i][j][k] = self.tmp[i][j][k];
-                }
-            }
+        if self.energy {
+            config.output.energy = true;
         }
-    }
-
-    pub fn draw(&self, i: usize) {
-        let out_file_name = format!("{:04}", i).to_string() + ".png";
-
-        let root_area = BitMapBackend::new(&out_file_name, (2560, 1440)).into_drawing_area();
-
-        root_area.fill(&WHITE).unwrap();
-
-        let root_area = root_area
-            .titled("3D, u_tt = u_xx + u_yy + u_zz.", ("sans-serif", 100))
-            .unwrap();
-
-        let (upper, lower) = root_area.split_vertically(720);
-
-        let drawing_areas = upper.split_evenly((1, 2));
-
-        let mut cc0 = ChartBuilder::on(&drawing_areas[0])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("x",), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc0.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc0.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[i][self.num_grid / 2][self.num_grid / 2]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let mut cc1 = ChartBuilder::on(&drawing_areas[1])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("y",), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc1.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc1.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[self.num_grid / 2][i][self.num_grid / 2]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let drawing_areas = lower.split_evenly((1, 2));
-
-        let mut cc2 = ChartBuilder::on(&drawing_areas[0])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("z"), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc2.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc2.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[self.num_grid / 2][self.num_grid / 2][i]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let mut cc3 = ChartBuilder::on(&drawing_areas[1])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!(""), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc3.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-    }
-
-    pub fn gen_apng(&self, num: usize) {
-        let mut files = vec![];
-
-        for i in 0..num {
-            files.push(format!("{:04}", i).to_string() + ".png");
+        if let Some(drift) = self.max_energy_drift {
+            config.output.max_energy_drift = Some(drift);
         }
-
-        let mut png_images: Vec<PNGImage> = Vec::new();
-
-        for f in files.iter() {
-            let mut file = File::open(f).unwrap();
-            let mut buffer = vec![];
-            file.read_to_end(&mut buffer).unwrap();
-            let img = image::load_from_memory(&buffer).unwrap();
-            png_images.push(load_dynamic_image(img).unwrap());
+        if !self.probes.is_empty() {
+            config.output.probes = self.probes.clone();
         }
-
-        let path = Path::new(r"wave_3d.png");
-        let mut out = BufWriter::new(File::create(path).unwrap());
-
-        let config = apng::create_config(&png_images, None).unwrap();
-        let mut encoder = Encoder::new(&mut out, config).unwrap();
-
-        for image in png_images.iter() {
-            let frame = Frame {
-                delay_num: Some(1),
-                delay_den: Some(20),
-                ..Default::default()
-            };
-            encoder.write_frame(image, frame).unwrap();
+        if self.radial_profile {
+            config.output.radial_profile = true;
         }
+        Ok(config)
+    }
 
-        match encoder.finish_encode() {
-            Ok(_n) => println!("success"),
-            Err(err) => eprintln!("{}", err),
-        }
+    /// Creates the output directory, writes the resolved configurations into it,
+    /// as JSON if the input file was JSON and as TOML otherwise, and reports the PML.
+    /// A single configuration goes to `config.toml`, several to `config_{dim}d.toml`.
+    fn prepare_output(&self, configs: &[SimulationConfig]) -> Result<()> {
+        let Some(config) = configs.first() else {
+            return Ok(());
+        };
+        fs::create_dir_all(&config.output.dir)?;
+        let is_json = self
+            .config
+            .as_ref()
+            .is_some_and(|path| path.extension().is_some_and(|ext| ext == "json"));
+        let extension = if is_json { "json" } else { "toml" };
+        for c in configs {
+            let name = if configs.len() == 1 {
+                format!("config.{}", extension)
+            } else {
+                format!("config_{}d.{}", c.dim, extension)
+            };
+            c.save(&config.output.dir.join(name))?;
+        }
+        if let Some(pml) = &config.pml {
+            let measured = pml.measured_reflection(
+                config.size,
+                config.domain,
+                config.stencil,
+                config.courant,
+            )?;
+            println!(
+                "pml: {} points, target reflection {:.1e} at normal incidence ({:.1e} at 45 degrees), \
+                 measured {:.1e} at normal incidence",
+                pml.thickness,
+                pml.target_reflection(0.0),
+                pml.target_reflection(std::f64::consts::FRAC_PI_4),
+                measured
+            );
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug)]
-pub struct Grid2D {
-    pub delta_t: f64,
-    pub delta_x: f64,
-    pub num_grid: usize,
-    pub x_1: Vec<Vec<f64>>,
-    pub x_2: Vec<Vec<f64>>,
-    pub tmp: Vec<Vec<f64>>,
+/// Discrete energy of one or more grids, written to `energy.csv` after every step.
+struct EnergyLog {
+    out: BufWriter<fs::File>,
+    /// Energy of every grid at the first record.
+    initial: Vec<f64>,
+    /// Energy of every grid at the last record.
+    previous: Vec<f64>,
+    limit: Option<f64>,
 }
 
-impl Grid2D {
-    pub fn new(dim: usize) -> Self {
-        Grid2D {
-            delta_t: (2.0 / (dim as f64 - 1.0)).powi(2),
-            delta_x: 2.0 / (dim as f64 - 1.0),
-            num_grid: dim,
-            x_1: vec![vec![0.0; dim]; dim],
-            x_2: vec![vec![0.0; dim]; dim],
-            tmp: vec![vec![0.0; dim]; dim],
-        }
+impl EnergyLog {
+    /// Opens `energy.csv` in the output directory if the configuration asks for it.
+    fn create(config: &SimulationConfig) -> Result<Option<Self>> {
+        let output = &config.output;
+        if !output.energy && output.max_energy_drift.is_none() {
+            return Ok(None);
+        }
+        let mut out = BufWriter::new(fs::File::create(output.dir.join("energy.csv"))?);
+        writeln!(out, "time,dim,energy,drift_per_step,drift")?;
+        Ok(Some(EnergyLog {
+            out,
+            initial: Vec::new(),
+            previous: Vec::new(),
+            limit: output.max_energy_drift,
+        }))
     }
 
-    pub fn initialize(&mut self) {
-        // x in [-1, 1]
-        for i in 0..self.num_grid {
-            let x = -1.0 + 2.0 * i as f64 / (self.num_grid as f64 - 1.0);
-            for j in 0..self.num_grid {
-                let y = -1.0 + 2.0 * j as f64 / (self.num_grid as f64 - 1.0);
-                self.x_1[i][j] = (-40.0 * (x * x + y * y)).exp();
-                self.x_2[i][j] = (-40.0 * (x * x + y * y)).exp();
+    /// Appends the energy of every grid, with its change over the last step and
+    /// since the first record relative to the first record, and fails if the
+    /// change over the step exceeds the limit. A grid that starts without
+    /// energy reports absolute changes instead, which keeps the limit in force.
+    fn record(&mut self, grids: &[&GridND]) -> Result<()> {
+        let energies: Vec<f64> = grids.iter().map(|g| g.energy()).collect();
+        if self.initial.is_empty() {
+            self.initial = energies.clone();
+            self.previous = energies.clone();
+        }
+        for (k, grid) in grids.iter().enumerate() {
+            let energy = energies[k];
+            let initial = match self.initial[k].abs() {
+                0.0 => 1.0,
+                initial => initial,
+            };
+            let drift = (energy - self.previous[k]) / initial;
+            writeln!(
+                self.out,
+                "{},{},{},{},{}",
+                grid.time,
+                grid.ndim,
+                energy,
+                drift,
+                (energy - self.initial[k]) / initial
+            )?;
+            if let Some(limit) = self.limit.filter(|&limit| drift.abs() > limit) {
+                self.out.flush()?;
+                return Err(Error::EnergyDrift {
+                    time: grid.time,
+                    drift,
+                    limit,
+                });
             }
         }
-        for i in 0..self.num_grid {
-            self.x_1[i][0] = 0.0;
-            self.x_1[self.num_grid - 1][0] = 0.0;
-            self.x_1[0][i] = 0.0;
-            self.x_1[0][self.num_grid - 1] = 0.0;
-        }
+        self.previous = energies;
+        Ok(())
     }
+}
+
+/// `u` of one or more grids at the configured probes, written to `probes.csv`
+/// after every step with one column per grid and probe.
+struct ProbeLog {
+    out: BufWriter<fs::File>,
+    probes: Vec<Vec<f64>>,
+}
 
-    pub fn step(&mut self) {
-        for i in 1..(self.num_grid - 1) {
-            for j in 1..(self.num_grid - 1) {
-                self.tmp[i][j] = 2.0 * self.x_1[i][j] - self.x_2[i][j]
-                    + self.delta_t * self.delta_t / (self.delta_x * self.delta_x)
-                        * (self.x_1[i + 1][j] - 2.0 * self.x_1[i][j]
-                            + self.x_1[i - 1][j]
-                            + self.x_1[i][j + 1]
-                            - 2.0 * self.x_1[i][j]
-                            + self.x_1[i][j - 1]);
+impl ProbeLog {
+    /// Opens `probes.csv` in the output directory if the configuration has probes,
+    /// naming the columns after the dimension and the probe, e.g. `3d_p0`.
+    fn create(config: &SimulationConfig, dims: &[usize]) -> Result<Option<Self>> {
+        let output = &config.output;
+        if output.probes.is_empty() {
+            return Ok(None);
+        }
+        let mut out = BufWriter::new(fs::File::create(output.dir.join("probes.csv"))?);
+        write!(out, "time")?;
+        for dim in dims {
+            for k in 0..output.probes.len() {
+                write!(out, ",{}d_p{}", dim, k)?;
             }
         }
-        for i in 1..(self.num_grid - 1) {
-            for j in 1..(self.num_grid - 1) {
-                self.x_2[i][j] = self.x_1[i][j];
-                self.x_1[i][j] = self.tmp[i][j];
-            }
+        writeln!(out)?;
+        for (k, probe) in output.probes.iter().enumerate() {
+            println!("probe p{}: {:?}", k, probe);
         }
+        Ok(Some(ProbeLog {
+            out,
+            probes: output.probes.clone(),
+        }))
     }
 
-    pub fn draw(&self, i: usize) {
-        let out_file_name = format!("{:04}", i).to_string() + ".png";
-
-        let root_area = BitMapBackend::new(&out_file_name, (2560, 1440)).into_drawing_area();
-
-        root_area.fill(&WHITE).unwrap();
-
-        let root_area = root_area
-            .titled("2D, u_tt = u_xx + u_yy.", ("sans-serif", 100))
-            .unwrap();
-
-        let (upper, lower) = root_area.split_vertically(720);
-
-        let drawing_areas = upper.split_evenly((1, 2));
-
-        let mut cc0 = ChartBuilder::on(&drawing_areas[0])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("x",), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc0.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc0.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[i][self.num_grid / 2]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let mut cc1 = ChartBuilder::on(&drawing_areas[1])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("y",), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc1.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc1.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[self.num_grid / 2][i]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let drawing_areas = lower.split_evenly((1, 2));
-
-        let mut cc2 = ChartBuilder::on(&drawing_areas[0])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!(""), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc2.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        let mut cc3 = ChartBuilder::on(&drawing_areas[1])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!(""), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc3.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-    }
-
-    pub fn gen_apng(&self, num: usize) {
-        let mut files = vec![];
-
-        for i in 0..num {
-            files.push(format!("{:04}", i).to_string() + ".png");
-        }
-
-        let mut png_images: Vec<PNGImage> = Vec::new();
-
-        for f in files.iter() {
-            let mut file = File::open(f).unwrap();
-            let mut buffer = vec![];
-            file.read_to_end(&mut buffer).unwrap();
-            let img = image::load_from_memory(&buffer).unwrap();
-            png_images.push(load_dynamic_image(img).unwrap());
-        }
-
-        let path = Path::new(r"wave_2d.png");
-        let mut out = BufWriter::new(File::create(path).unwrap());
-
-        let config = apng::create_config(&png_images, None).unwrap();
-        let mut encoder = Encoder::new(&mut out, config).unwrap();
-
-        for image in png_images.iter() {
-            let frame = Frame {
-                delay_num: Some(1),
-                delay_den: Some(20),
-                ..Default::default()
-            };
-            encoder.write_frame(image, frame).unwrap();
-        }
-
-        match encoder.finish_encode() {
-            Ok(_n) => println!("success"),
-            Err(err) => eprintln!("{}", err),
+    fn record(&mut self, grids: &[&GridND]) -> Result<()> {
+        write!(self.out, "{}", grids[0].time)?;
+        for grid in grids {
+            for probe in &self.probes {
+                write!(self.out, ",{}", grid.value_at(probe))?;
+            }
         }
+        writeln!(self.out)?;
+        Ok(())
     }
 }
 
-#[derive(Debug)]
-pub struct Grid1D {
-    pub delta_t: f64,
-    pub delta_x: f64,
-    pub num_grid: usize,
-    pub x_1: Vec<f64>,
-    pub x_2: Vec<f64>,
-    pub tmp: Vec<f64>,
+/// Radial profiles of one or more grids at every frame, appended to `profile.csv`
+/// and drawn into the `profile` subdirectory.
+struct ProfileLog {
+    out: BufWriter<fs::File>,
+    dir: PathBuf,
 }
 
-impl Grid1D {
-    pub fn new(dim: usize) -> Self {
-        Grid1D {
-            delta_t: (2.0 / (dim as f64 - 1.0)).powi(2),
-            delta_x: 2.0 / (dim as f64 - 1.0),
-            num_grid: dim,
-            x_1: vec![0.0; dim],
-            x_2: vec![0.0; dim],
-            tmp: vec![0.0; dim],
-        }
+impl ProfileLog {
+    fn create(config: &SimulationConfig) -> Result<Option<Self>> {
+        let output = &config.output;
+        if !output.radial_profile {
+            return Ok(None);
+        }
+        let dir = output.dir.join("profile");
+        fs::create_dir_all(&dir)?;
+        let mut out = BufWriter::new(fs::File::create(output.dir.join("profile.csv"))?);
+        writeln!(out, "time,dim,radius,count,mean,spread,min,max")?;
+        Ok(Some(ProfileLog { out, dir }))
     }
 
-    pub fn initialize(&mut self) {
-        // x in [-1, 1]
-        for i in 0..self.num_grid {
-            let x = -1.0 + 2.0 * i as f64 / (self.num_grid as f64 - 1.0);
-            self.x_1[i] = (-40.0 * (x * x)).exp();
-            self.x_2[i] = (-40.0 * (x * x)).exp();
+    /// Bins every grid into shells one grid spacing thick and draws frame `i`.
+    fn record(&mut self, grids: &[&GridND], i: usize) -> Result<()> {
+        let profiles: Vec<_> = grids.iter().map(|g| radial_profile(g, g.delta_x)).collect();
+        for (grid, bins) in grids.iter().zip(&profiles) {
+            for b in bins {
+                writeln!(
+                    self.out,
+                    "{},{},{},{},{},{},{},{}",
+                    grid.time, grid.ndim, b.radius, b.count, b.mean, b.spread, b.min, b.max
+                )?;
+            }
         }
-        self.x_1[0] = 0.0;
-        self.x_1[self.num_grid - 1] = 0.0;
+        render::draw_profiles(grids, &profiles, &self.dir, i)?;
+        Ok(())
     }
 
-    pub fn step(&mut self) {
-        for i in 1..(self.num_grid - 1) {
-            self.tmp[i] = 2.0 * self.x_1[i] - self.x_2[i]
-                + self.delta_t * self.delta_t / (self.delta_x * self.delta_x)
-                    * (self.x_1[i + 1] - 2.0 * self.x_1[i] + self.x_1[i - 1]);
-        }
-        for i in 1..(self.num_grid - 1) {
-            self.x_2[i] = self.x_1[i];
-            self.x_1[i] = self.tmp[i];
-        }
+    /// Flushes the table and animates the first `frames` plots into `name`.
+    fn finish(mut self, frames: usize, name: &str) -> Result<()> {
+        self.out.flush()?;
+        let out = self.dir.parent().unwrap_or(&self.dir).join(name);
+        render::gen_apng(&self.dir, frames, &out)?;
+        Ok(())
     }
+}
 
-    pub fn draw(&self, i: usize) {
-        let out_file_name = format!("{:04}", i).to_string() + ".png";
-
-        let root_area = BitMapBackend::new(&out_file_name, (2560, 1440)).into_drawing_area();
-
-        root_area.fill(&WHITE).unwrap();
-
-        let root_area = root_area
-            .titled("1D, u_tt = u_xx.", ("sans-serif", 100))
-            .unwrap();
-
-        let (upper, lower) = root_area.split_vertically(720);
-
-        let drawing_areas = upper.split_evenly((1, 2));
-
-        let mut cc0 = ChartBuilder::on(&drawing_areas[0])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("x",), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc0.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        cc0.draw_series(LineSeries::new(
-            (0..self.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f32 / (self.num_grid as f32 - 1.0)) as f32,
-                    (self.x_1[i]) as f32,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-        let mut cc1 = ChartBuilder::on(&drawing_areas[1])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!("",), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc1.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        let drawing_areas = lower.split_evenly((1, 2));
-
-        let mut cc2 = ChartBuilder::on(&drawing_areas[0])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!(""), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc2.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-
-        let mut cc3 = ChartBuilder::on(&drawing_areas[1])
-            .x_label_area_size(30)
-            .y_label_area_size(30)
-            .margin_right(20)
-            .caption(format!(""), ("sans-serif", 60))
-            .build_cartesian_2d(-1f32..1f32, -1f32..1f32)
-            .unwrap();
-        cc3.configure_mesh()
-            .x_labels(5)
-            .y_labels(3)
-            .max_light_lines(4)
-            .draw()
-            .unwrap();
-    }
+/// Energy and probe logs, whichever the configuration asks for.
+struct Monitors {
+    energy: Option<EnergyLog>,
+    probes: Option<ProbeLog>,
+}
 
-    pub fn gen_apng(&self, num: usize) {
-        let mut files = vec![];
+impl Monitors {
+    fn create(config: &SimulationConfig, dims: &[usize]) -> Result<Self> {
+        Ok(Monitors {
+            energy: EnergyLog::create(config)?,
+            probes: ProbeLog::create(config, dims)?,
+        })
+    }
 
-        for i in 0..num {
-            files.push(format!("{:04}", i).to_string() + ".png");
+    fn record(&mut self, grids: &[&GridND]) -> Result<()> {
+        if let Some(log) = &mut self.energy {
+            log.record(grids)?;
         }
-
-        let mut png_images: Vec<PNGImage> = Vec::new();
-
-        for f in files.iter() {
-            let mut file = File::open(f).unwrap();
-            let mut buffer = vec![];
-            file.read_to_end(&mut buffer).unwrap();
-            let img = image::load_from_memory(&buffer).unwrap();
-            png_images.push(load_dynamic_image(img).unwrap());
-        }
-
-        let path = Path::new(r"wave_1d.png");
-        let mut out = BufWriter::new(File::create(path).unwrap());
-
-        let config = apng::create_config(&png_images, None).unwrap();
-        let mut encoder = Encoder::new(&mut out, config).unwrap();
-
-        for image in png_images.iter() {
-            let frame = Frame {
-                delay_num: Some(1),
-                delay_den: Some(20),
-                ..Default::default()
-            };
-            encoder.write_frame(image, frame).unwrap();
+        if let Some(log) = &mut self.probes {
+            log.record(grids)?;
         }
+        Ok(())
+    }
+}
 
-        match encoder.finish_encode() {
-            Ok(_n) => println!("success"),
-            Err(err) => eprintln!("{}", err),
+fn run(dim: Option<usize>, sim: &SimArgs) -> Result<()> {
+    let config = sim.resolve(dim)?;
+    sim.prepare_output(std::slice::from_ref(&config))?;
+    let output = &config.output;
+    let mut grid = config.build_grid()?;
+    let mut monitors = Monitors::create(&config, &[config.dim])?;
+    let mut profiles = ProfileLog::create(&config)?;
+    monitors.record(&[&grid])?;
+    for i in 0..output.frames {
+        render::draw(&grid, &output.dir, i)?;
+        if let Some(log) = &mut profiles {
+            log.record(&[&grid], i)?;
+        }
+        for _ in 0..output.interval {
+            grid.step();
+            monitors.record(&[&grid])?;
         }
     }
+    let out = output.dir.join(format!("wave_{}d.png", config.dim));
+    render::gen_apng(&output.dir, output.frames, &out)?;
+    if let Some(log) = profiles {
+        log.finish(output.frames, &format!("profile_{}d.png", config.dim))?;
+    }
+    Ok(())
 }
 
-pub fn draw(vec1: &Grid1D, vec2: &Grid2D, vec3: &Grid3D, vec4: &Grid4D, i: usize) {
-    let out_file_name = format!("{:04}", i).to_string() + ".png";
-
-    let root = BitMapBackend::new(&out_file_name, (2560, 1440)).into_drawing_area();
-
-    root.fill(&WHITE).unwrap();
-
-    let mut chart = ChartBuilder::on(&root)
-        .margin(5)
-        .x_label_area_size(30)
-        .y_label_area_size(30)
-        .build_cartesian_2d(-1.0..1.0, -1.0..1.0)
-        .unwrap();
-
-    chart.configure_mesh().draw().unwrap();
-
-    chart
-        .draw_series(LineSeries::new(
-            (0..vec1.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f64 / (vec3.num_grid as f64 - 1.0)),
-                    vec1.x_1[i] as f64,
-                )
-            }),
-            &BLACK,
-        ))
-        .unwrap();
-
-    chart
-        .draw_series(LineSeries::new(
-            (0..vec2.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f64 / (vec3.num_grid as f64 - 1.0)),
-                    vec2.x_1[i][vec2.x_1.len() / 2] as f64,
-                )
-            }),
-            &RED,
-        ))
-        .unwrap();
-
-    chart
-        .draw_series(LineSeries::new(
-            (0..vec3.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f64 / (vec3.num_grid as f64 - 1.0)),
-                    vec3.x_1[i][vec3.x_1.len() / 2][vec3.x_1.len() / 2] as f64,
-                )
-            }),
-            &GREEN,
-        ))
-        .unwrap();
-
-    chart
-        .draw_series(LineSeries::new(
-            (0..vec4.x_1.len()).map(|i| {
-                (
-                    (-1.0 + 2.0 * i as f64 / (vec4.num_grid as f64 - 1.0)),
-                    vec4.x_1[i][vec4.x_1.len() / 2][vec4.x_1.len() / 2][vec4.x_1.len() / 2] as f64,
-                )
-            }),
-            &BLUE,
-        ))
-        .unwrap();
-
-    root.present().unwrap();
+fn compare(dims: &[usize], sim: &SimArgs) -> Result<()> {
+    let configs = dims
+        .iter()
+        .map(|&dim| sim.resolve(Some(dim)))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(config) = configs.first() else {
+        return Ok(());
+    };
+    let output = &config.output;
+    let mut grids = configs
+        .iter()
+        .map(|c| c.build_grid())
+        .collect::<Result<Vec<_>>>()?;
+    // Frames must show every dimension at the same time, so all grids take the
+    // smallest of their time steps and start again from the initial data.
+    let delta_t = grids
+        .iter()
+        .map(|g| g.delta_t)
+        .fold(f64::INFINITY, f64::min);
+    for (grid, c) in grids.iter_mut().zip(configs.iter()) {
+        grid.set_time_step(delta_t)?;
+        grid.initialize_from(&c.displacement, &c.velocity);
+    }
+    // Each echoed configuration reruns its dimension with the shared step.
+    let echoed: Vec<_> = configs
+        .iter()
+        .map(|c| SimulationConfig {
+            delta_t: Some(delta_t),
+            ..c.clone()
+        })
+        .collect();
+    sim.prepare_output(&echoed)?;
+    let mut monitors = Monitors::create(config, dims)?;
+    let mut profiles = ProfileLog::create(config)?;
+    monitors.record(&grids.iter().collect::<Vec<_>>())?;
+    for i in 0..output.frames {
+        render::draw_comparison(&grids.iter().collect::<Vec<_>>(), &output.dir, i)?;
+        if let Some(log) = &mut profiles {
+            log.record(&grids.iter().collect::<Vec<_>>(), i)?;
+        }
+        for _ in 0..output.interval {
+            for grid in grids.iter_mut() {
+                grid.step();
+            }
+            monitors.record(&grids.iter().collect::<Vec<_>>())?;
+        }
+    }
+    let name = dims
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join("_");
+    let out = output.dir.join(format!("wave_{}d.png", name));
+    render::gen_apng(&output.dir, output.frames, &out)?;
+    if let Some(log) = profiles {
+        log.finish(output.frames, &format!("profile_{}d.png", name))?;
+    }
+    Ok(())
 }
 
-pub fn gen_apng(num: usize) {
-    let mut files = vec![];
-
-    for i in 0..num {
-        files.push(format!("{:04}", i).to_string() + ".png");
+/// Runs the radial solver to exactly `until`, shortening the time step as needed,
+/// writes `radial.csv` and, with `check`, compares with the full grid of the configuration.
+fn radial(dim: Option<f64>, points: usize, until: f64, check: bool, sim: &SimArgs) -> Result<()> {
+    if !(until > 0.0 && until.is_finite()) {
+        return Err(Error::InvalidConfig(format!(
+            "--until must be positive, got {}",
+            until
+        )));
     }
-
-    let mut png_images: Vec<PNGImage> = Vec::new();
-
-    for f in files.iter() {
-        let mut file = File::open(f).unwrap();
-        let mut buffer = vec![];
-        file.read_to_end(&mut buffer).unwrap();
-        let img = image::load_from_memory(&buffer).unwrap();
-        png_images.push(load_dynamic_image(img).unwrap());
+    // An integer --dim also selects the full grid for --check, which is the
+    // only grid whose size is limited.
+    let integer = dim.filter(|d| d.fract() == 0.0 && *d >= 1.0);
+    let config = sim.configure(integer.map(|d| d as usize))?;
+    let dim = dim.unwrap_or(config.dim as f64);
+    if check {
+        if dim != config.dim as f64 {
+            return Err(Error::InvalidConfig(format!(
+                "--check needs an integer dim, got {}",
+                dim
+            )));
+        }
+        config.validate()?;
+    } else {
+        config.validate_settings()?;
+    }
+    config.apply_threads()?;
+    InitialCondition::check_radial(&config.displacement, &config.velocity, "the radial solver")?;
+    let [lower, upper] = config.domain;
+    let mut grid = RadialGrid::new(dim, points, upper.max(-lower));
+    grid.boundary = config.boundary;
+    grid.validate()?;
+    grid.set_courant(config.courant)?;
+    grid.set_time_step(until / (until / grid.delta_t).ceil())?;
+    grid.initialize_from(&config.displacement, &config.velocity);
+    while grid.time < until - 0.5 * grid.delta_t {
+        grid.step();
     }
 
-    let path = Path::new(r"wave_1_2_3_4d.png");
-    let mut out = BufWriter::new(File::create(path).unwrap());
-
-    let config = apng::create_config(&png_images, None).unwrap();
-    let mut encoder = Encoder::new(&mut out, config).unwrap();
-
-    for image in png_images.iter() {
-        let frame = Frame {
-            delay_num: Some(1),
-            delay_den: Some(20),
-            ..Default::default()
-        };
-        encoder.write_frame(image, frame).unwrap();
+    fs::create_dir_all(&config.output.dir)?;
+    let path = config.output.dir.join("radial.csv");
+    let mut out = BufWriter::new(fs::File::create(&path)?);
+    writeln!(out, "r,u")?;
+    for (i, u) in grid.u_1.iter().enumerate() {
+        writeln!(out, "{},{}", grid.coordinate(i), u)?;
+    }
+    out.flush()?;
+    println!("u(r) at t = {} written to {}", grid.time, path.display());
+
+    if check {
+        let full = advance(&config, until)?;
+        let deviation = full
+            .center_line(0)
+            .iter()
+            .enumerate()
+            .map(|(i, u)| (u - grid.value_at(full.coordinate(i).abs())).abs())
+            .fold(0.0, f64::max);
+        println!(
+            "max |u_grid - u_radial| along the first axis at t = {}: {:e}",
+            full.time, deviation
+        );
     }
+    Ok(())
+}
 
-    match encoder.finish_encode() {
-        Ok(_n) => println!("success"),
-        Err(err) => eprintln!("{}", err),
+/// Builds the grid of `config` with the largest time step that reaches `until`
+/// in a whole number of steps, and runs it there.
+fn advance(config: &SimulationConfig, until: f64) -> Result<GridND> {
+    let mut grid = config.build_grid()?;
+    grid.set_time_step(until / (until / grid.delta_t).ceil())?;
+    grid.initialize_from(&config.displacement, &config.velocity);
+    while grid.time < until - 0.5 * grid.delta_t {
+        grid.step();
     }
+    Ok(grid)
 }
-fn main() {
-    let sim_num = 1200;
-    let interval = 10;
-    let size = 65;
-    // 4D
-    let mut vec_4d = Grid4D::new(size);
-    vec_4d.initialize();
-    for i in 0..sim_num {
-        vec_4d.draw(i);
-        for _ in 0..interval {
-            vec_4d.step();
-        }
+
+/// Runs every size to every time, compares with the analytic solution or the
+/// finest grid, and writes the errors and observed orders to `convergence.csv`.
+fn convergence(
+    dim: Option<usize>,
+    sizes: &[usize],
+    times: &[f64],
+    finest: bool,
+    sim: &SimArgs,
+) -> Result<()> {
+    if let Some(&time) = times.iter().find(|&&t| !(t > 0.0 && t.is_finite())) {
+        return Err(Error::InvalidConfig(format!(
+            "--times must be positive, got {}",
+            time
+        )));
     }
-    vec_4d.gen_apng(sim_num);
-    // 3D
-    let mut vec_3d = Grid3D::new(size);
-    vec_3d.initialize();
-    for i in 0..sim_num {
-        vec_3d.draw(i);
-        for _ in 0..interval {
-            vec_3d.step();
+    // Only the sizes that are run have to fit in the grid limit.
+    let base = sim.configure(dim)?;
+    base.validate_settings()?;
+    base.apply_threads()?;
+    let configs = sizes
+        .iter()
+        .map(|&size| {
+            let mut config = base.clone();
+            config.size = size;
+            config.validate()?;
+            Ok(config)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let (compared, reference) = match (finest, configs.split_last()) {
+        (true, Some((last, rest))) if !rest.is_empty() => (rest, Some(last)),
+        (true, _) => {
+            return Err(Error::InvalidConfig(
+                "--finest needs at least two sizes".to_string(),
+            ))
+        }
+        (false, _) => (&configs[..], None),
+    };
+    let analytic = if finest {
+        None
+    } else {
+        Some(Analytic::new(
+            base.dim,
+            base.displacement.clone(),
+            base.velocity.clone(),
+        )?)
+    };
+
+    fs::create_dir_all(&base.output.dir)?;
+    let path = base.output.dir.join("convergence.csv");
+    let mut out = BufWriter::new(fs::File::create(&path)?);
+    writeln!(
+        out,
+        "time,size,delta_x,l1,l2,linf,order_l1,order_l2,order_linf"
+    )?;
+    println!(
+        "{:>8} {:>6} {:>10} {:>10} {:>10} {:>10} {:>6} {:>6} {:>6}",
+        "time", "size", "delta_x", "L1", "L2", "Linf", "p_L1", "p_L2", "p_Linf"
+    );
+    for &time in times {
+        let reference = reference.map(|c| advance(c, time)).transpose()?;
+        let mut previous: Option<(f64, ErrorNorms)> = None;
+        for config in compared {
+            let grid = advance(config, time)?;
+            let norms = match (&reference, &analytic) {
+                (Some(fine), _) => ErrorNorms::between(&grid, fine)?,
+                (None, Some(analytic)) => analytic.errors(&grid),
+                (None, None) => unreachable!("either a reference grid or an analytic solution"),
+            };
+            // Observed order log(e_coarse / e_fine) / log(dx_coarse / dx_fine).
+            let orders = previous.map_or([f64::NAN; 3], |(dx, e)| {
+                let ratio = (dx / grid.delta_x).ln();
+                [
+                    (e.l1 / norms.l1).ln() / ratio,
+                    (e.l2 / norms.l2).ln() / ratio,
+                    (e.linf / norms.linf).ln() / ratio,
+                ]
+            });
+            println!(
+                "{:>8} {:>6} {:>10.3e} {:>10.3e} {:>10.3e} {:>10.3e} {:>6.2} {:>6.2} {:>6.2}",
+                time,
+                config.size,
+                grid.delta_x,
+                norms.l1,
+                norms.l2,
+                norms.linf,
+                orders[0],
+                orders[1],
+                orders[2]
+            );
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{}",
+                time,
+                config.size,
+                grid.delta_x,
+                norms.l1,
+                norms.l2,
+                norms.linf,
+                orders[0],
+                orders[1],
+                orders[2]
+            )?;
+            previous = Some((grid.delta_x, norms));
         }
     }
-    vec_3d.gen_apng(sim_num);
-    // 2D
-    let mut vec_2d = Grid2D::new(size);
-    vec_2d.initialize();
-    for i in 0..sim_num {
-        vec_2d.draw(i);
-        for _ in 0..interval {
-            vec_2d.step();
+    out.flush()?;
+    println!("errors written to {}", path.display());
+    Ok(())
+}
+
+/// Measures the tail behind the front at radius `radius` for every dimension,
+/// prints a table and writes `huygens.csv`, `huygens_signal.csv` and `huygens.png`.
+fn huygens(dims: &[f64], radius: f64, window: f64, points: usize, sim: &SimArgs) -> Result<()> {
+    let config = sim.resolve(None)?;
+    let [lower, upper] = config.domain;
+    let pulse = RadialPulse::new(
+        config.displacement.clone(),
+        config.velocity.clone(),
+        upper.max(-lower),
+        points,
+        config.courant,
+    );
+    let support = pulse.support;
+    let report = Huygens {
+        pulse,
+        probe: radius,
+        window,
+    };
+    report.validate()?;
+    let tails = dims
+        .iter()
+        .map(|&dim| report.measure(dim))
+        .collect::<Result<Vec<_>>>()?;
+
+    fs::create_dir_all(&config.output.dir)?;
+    let mut summary = BufWriter::new(fs::File::create(config.output.dir.join("huygens.csv"))?);
+    writeln!(summary, "dim,peak,residual,relative_residual,tail_energy")?;
+    let mut signals = BufWriter::new(fs::File::create(
+        config.output.dir.join("huygens_signal.csv"),
+    )?);
+    writeln!(signals, "dim,time,u")?;
+    println!(
+        "probe at r = {}, data within r <= {:.3}, front passed at t = {:.3}",
+        radius,
+        support,
+        radius + support
+    );
+    println!(
+        "{:>6} {:>10} {:>10} {:>10} {:>12}",
+        "dim", "peak", "residual", "relative", "tail energy"
+    );
+    for tail in &tails {
+        let relative = tail.residual / tail.peak;
+        println!(
+            "{:>6} {:>10.3e} {:>10.3e} {:>10.3e} {:>12.3e}",
+            tail.dim, tail.peak, tail.residual, relative, tail.tail_energy
+        );
+        writeln!(
+            summary,
+            "{},{},{},{},{}",
+            tail.dim, tail.peak, tail.residual, relative, tail.tail_energy
+        )?;
+        for (t, u) in &tail.signal {
+            writeln!(signals, "{},{},{}", tail.dim, t, u)?;
         }
     }
-    vec_2d.gen_apng(sim_num);
-    // 1D
-    let mut vec_1d = Grid1D::new(size);
-    vec_1d.initialize();
-    for i in 0..sim_num {
-        vec_1d.draw(i);
-        for _ in 0..interval {
-            vec_1d.step();
+    summary.flush()?;
+    signals.flush()?;
+    let labelled: Vec<_> = tails
+        .into_iter()
+        .map(|tail| (format!("{}D", tail.dim), tail.signal))
+        .collect();
+    let plot = config.output.dir.join("huygens.png");
+    render::draw_signals(&labelled, &plot)?;
+    println!("report written to {}", config.output.dir.display());
+    Ok(())
+}
+
+/// Fits the decay of the front for every dimension, prints a table and writes
+/// `decay.csv`, `decay_peaks.csv` and `decay.png`.
+fn decay(dims: &[f64], start: f64, until: f64, points: usize, sim: &SimArgs) -> Result<()> {
+    let config = sim.resolve(None)?;
+    let [lower, upper] = config.domain;
+    let report = Decay {
+        pulse: RadialPulse::new(
+            config.displacement.clone(),
+            config.velocity.clone(),
+            upper.max(-lower),
+            points,
+            config.courant,
+        ),
+        start,
+        until,
+    };
+    report.validate()?;
+    let fits = dims
+        .iter()
+        .map(|&dim| report.measure(dim))
+        .collect::<Result<Vec<_>>>()?;
+
+    fs::create_dir_all(&config.output.dir)?;
+    let mut summary = BufWriter::new(fs::File::create(config.output.dir.join("decay.csv"))?);
+    writeln!(summary, "dim,expected,exponent,prefactor,r_squared")?;
+    let mut peaks = BufWriter::new(fs::File::create(config.output.dir.join("decay_peaks.csv"))?);
+    writeln!(peaks, "dim,time,peak")?;
+    println!(
+        "front peak fitted to c t^-p over {} <= t <= {}, data within r <= {:.3}",
+        start, until, report.pulse.support
+    );
+    println!(
+        "{:>6} {:>8} {:>8} {:>8} {:>10}",
+        "dim", "(N-1)/2", "p", "p - exp", "R^2"
+    );
+    for fit in &fits {
+        println!(
+            "{:>6} {:>8.3} {:>8.4} {:>8.4} {:>10.6}",
+            fit.dim,
+            fit.expected,
+            fit.fit.exponent,
+            fit.fit.exponent - fit.expected,
+            fit.fit.r_squared
+        );
+        writeln!(
+            summary,
+            "{},{},{},{},{}",
+            fit.dim, fit.expected, fit.fit.exponent, fit.fit.prefactor, fit.fit.r_squared
+        )?;
+        for (t, peak) in &fit.peaks {
+            writeln!(peaks, "{},{},{}", fit.dim, t, peak)?;
         }
     }
-    vec_1d.gen_apng(sim_num);
-
-    // 1,2,3,4D
-    /*
-    let mut vec_1d = Grid1D::new(size);
-    let mut vec_2d = Grid2D::new(size);
-    let mut vec_3d = Grid3D::new(size);
-    let mut vec_4d = Grid4D::new(size);
-    vec_1d.initialize();
-    vec_2d.initialize();
-    vec_3d.initialize();
-    vec_4d.initialize();
-    for i in 0..sim_num {
-        draw(&vec_1d, &vec_2d, &vec_3d, &vec_4d, i);
-        for _ in 0..interval {
-            vec_1d.step();
-            vec_2d.step();
-            vec_3d.step();
-            vec_4d.step();
+    summary.flush()?;
+    peaks.flush()?;
+    render::draw_decay(&fits, start, &config.output.dir.join("decay.png"))?;
+    println!("report written to {}", config.output.dir.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Run { dim, sim } => run(dim, &sim),
+        Command::Compare { dims, sim } => compare(&dims, &sim),
+        Command::Radial {
+            dim,
+            points,
+            until,
+            check,
+            sim,
+        } => radial(dim, points, until, check, &sim),
+        Command::Convergence {
+            dim,
+            sizes,
+            times,
+            finest,
+            sim,
+        } => convergence(dim, &sizes, &times, finest, &sim),
+        Command::Huygens {
+            dims,
+            radius,
+            window,
+            points,
+            sim,
+        } => huygens(&dims, radius, window, points, &sim),
+        Command::Decay {
+            dims,
+            start,
+            until,
+            points,
+            sim,
+        } => decay(&dims, start, until, points, &sim),
+        Command::Render {
+            frames,
+            input_dir,
+            output,
+        } => render::gen_apng(&input_dir, frames, &output),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
         }
     }
-    gen_apng(sim_num);
-    */
 }
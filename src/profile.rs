@@ -0,0 +1,60 @@
+//! Radial profile of a grid: every point binned by its distance to the origin.
+//!
+//! Unlike the center lines drawn by [`render::draw`](crate::render::draw), the
+//! bins use the whole field, and their spread shows how far the numerical
+//! solution departs from radial symmetry through grid anisotropy.
+
+use crate::grid::GridND;
+
+/// Statistics of `x_1` over the points with `inner <= r < inner + width`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadialBin {
+    /// Middle of the bin.
+    pub radius: f64,
+    pub count: usize,
+    pub mean: f64,
+    /// Standard deviation about `mean`.
+    pub spread: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Bins `grid.x_1` into shells of thickness `width` around the origin, up to
+/// the farthest point; empty shells are left out.
+pub fn radial_profile(grid: &GridND, width: f64) -> Vec<RadialBin> {
+    let mut idx = vec![0; grid.ndim];
+    let mut x = vec![0.0; grid.ndim];
+    // count, sum, sum of squares, min, max
+    let mut shells: Vec<(usize, f64, f64, f64, f64)> = Vec::new();
+    for p in 0..grid.x_1.len() {
+        grid.point(p, &mut idx, &mut x);
+        let r = x.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let k = (r / width) as usize;
+        if k >= shells.len() {
+            shells.resize(k + 1, (0, 0.0, 0.0, f64::INFINITY, f64::NEG_INFINITY));
+        }
+        let u = grid.x_1[p];
+        let shell = &mut shells[k];
+        shell.0 += 1;
+        shell.1 += u;
+        shell.2 += u * u;
+        shell.3 = shell.3.min(u);
+        shell.4 = shell.4.max(u);
+    }
+    shells
+        .into_iter()
+        .enumerate()
+        .filter(|(_, shell)| shell.0 > 0)
+        .map(|(k, (count, sum, squares, min, max))| {
+            let mean = sum / count as f64;
+            RadialBin {
+                radius: (k as f64 + 0.5) * width,
+                count,
+                mean,
+                spread: (squares / count as f64 - mean * mean).max(0.0).sqrt(),
+                min,
+                max,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,303 @@
+//! Radially symmetric reduction of the N-dimensional wave equation.
+//!
+//! For radial data the problem becomes `u_tt = u_rr + (N - 1) / r u_r` on
+//! `[0, radius]`, which is solved in the conservative form
+//! `r^{N-1} u_tt = (r^{N-1} u_r)_r` with the cell volumes
+//! `V_i = (r_{i+1/2}^N - r_{i-1/2}^N) / (N dr)`. At the origin this gives the
+//! regular limit `u_tt = N u_rr = 2N (u_1 - u_0) / dr^2`. `N` may be any
+//! positive real number.
+
+use crate::boundary::BoundaryCondition;
+use crate::error::{Error, Result};
+use crate::grid::DEFAULT_COURANT;
+use crate::initial::InitialCondition;
+
+/// Fewest points [`RadialGrid::validate`] accepts, enough for the cubic
+/// interpolation of [`RadialProfile`](crate::RadialProfile).
+pub const MIN_RADIAL_POINTS: usize = 4;
+
+/// Radial wave field on `[0, radius]` for dimension `dim`.
+#[derive(Debug, Clone)]
+pub struct RadialGrid {
+    pub dim: f64,
+    pub delta_t: f64,
+    pub delta_r: f64,
+    pub num_points: usize,
+    pub radius: f64,
+    /// Time of the current level `u_1`.
+    pub time: f64,
+    /// Condition at `r = radius`. Absorbing conditions of either order damp
+    /// outgoing spherical waves with the first-order Mur condition applied to
+    /// `r^{(N-1)/2} u`.
+    pub boundary: BoundaryCondition,
+    pub u_1: Vec<f64>,
+    pub u_2: Vec<f64>,
+    /// Level being computed; rotated with `u_1` and `u_2` like
+    /// [`GridND::tmp`](crate::GridND::tmp).
+    pub tmp: Vec<f64>,
+    /// `r_{i+1/2}^{N-1} / (dr^2 V_i)`, the weight of `u_{i+1} - u_i`.
+    outer: Vec<f64>,
+    /// `r_{i-1/2}^{N-1} / (dr^2 V_i)`, the weight of `u_i - u_{i-1}`.
+    inner: Vec<f64>,
+    /// `V_i dr = ∫ r^{N-1} dr` over the cell of point `i`.
+    volume: Vec<f64>,
+    /// Bound on the largest eigenvalue of the discrete operator.
+    spectral_radius: f64,
+}
+
+impl RadialGrid {
+    pub fn new(dim: f64, num_points: usize, radius: f64) -> Self {
+        let delta_r = radius / (num_points as f64 - 1.0);
+        let face = |i: usize| (i as f64 + 0.5) * delta_r;
+        let area: Vec<f64> = (0..num_points.saturating_sub(1))
+            .map(|i| face(i).powf(dim - 1.0))
+            .collect();
+        let volume: Vec<f64> = (0..num_points)
+            .map(|i| {
+                let outer = if i + 1 < num_points { face(i) } else { radius };
+                let inner = if i == 0 { 0.0 } else { face(i - 1) };
+                (outer.powf(dim) - inner.powf(dim)) / (dim * delta_r)
+            })
+            .collect();
+        let scale = |i: usize| (delta_r * delta_r * volume[i]).recip();
+        let outer: Vec<f64> = (0..num_points)
+            .map(|i| area.get(i).map_or(0.0, |a| a * scale(i)))
+            .collect();
+        let inner: Vec<f64> = (0..num_points)
+            .map(|i| if i == 0 { 0.0 } else { area[i - 1] * scale(i) })
+            .collect();
+        // Gershgorin on the operator symmetrized by the cell volumes.
+        let spectral_radius = (0..num_points)
+            .map(|i| {
+                let coupling =
+                    |j: usize, a: f64| a / (delta_r * delta_r * (volume[i] * volume[j]).sqrt());
+                let mut row = outer[i] + inner[i];
+                if i > 0 {
+                    row += coupling(i - 1, area[i - 1]);
+                }
+                if i + 1 < num_points {
+                    row += coupling(i + 1, area[i]);
+                }
+                row
+            })
+            .fold(0.0, f64::max);
+        let mut grid = RadialGrid {
+            dim,
+            delta_t: 0.0,
+            delta_r,
+            num_points,
+            radius,
+            time: 0.0,
+            boundary: BoundaryCondition::default(),
+            u_1: vec![0.0; num_points],
+            u_2: vec![0.0; num_points],
+            tmp: vec![0.0; num_points],
+            outer,
+            inner,
+            volume: volume.iter().map(|v| v * delta_r).collect(),
+            spectral_radius,
+        };
+        grid.delta_t = DEFAULT_COURANT * grid.max_time_step();
+        grid
+    }
+
+    /// Largest stable leapfrog time step `2 / sqrt(λ)`, with `λ` a bound on the
+    /// largest eigenvalue of the radial operator: `dr` for `N <= 2`, smaller
+    /// for larger `N`, where the origin row dominates.
+    pub fn max_time_step(&self) -> f64 {
+        2.0 / self.spectral_radius.sqrt()
+    }
+
+    pub fn courant(&self) -> f64 {
+        self.delta_t / self.max_time_step()
+    }
+
+    /// Sets `delta_t`, refusing steps that violate the CFL condition.
+    pub fn set_time_step(&mut self, delta_t: f64) -> Result<()> {
+        let limit = self.max_time_step();
+        if !(delta_t > 0.0 && delta_t <= limit) {
+            return Err(Error::Unstable { delta_t, limit });
+        }
+        self.delta_t = delta_t;
+        Ok(())
+    }
+
+    pub fn set_courant(&mut self, courant: f64) -> Result<()> {
+        self.set_time_step(courant * self.max_time_step())
+    }
+
+    /// Checks the dimension, the number of points and that the outer condition
+    /// makes sense on a ray.
+    pub fn validate(&self) -> Result<()> {
+        if self.num_points < MIN_RADIAL_POINTS {
+            return Err(Error::InvalidConfig(format!(
+                "the radial grid needs at least {} points, got {}",
+                MIN_RADIAL_POINTS, self.num_points
+            )));
+        }
+        if !(self.dim.is_finite() && self.dim > 0.0) {
+            return Err(Error::InvalidConfig(format!(
+                "radial dimension must be positive, got {}",
+                self.dim
+            )));
+        }
+        if self.boundary == BoundaryCondition::Periodic {
+            return Err(Error::InvalidConfig(
+                "the radial solver has no periodic boundary".to_string(),
+            ));
+        }
+        self.boundary.validate()
+    }
+
+    pub fn coordinate(&self, i: usize) -> f64 {
+        self.delta_r * i as f64
+    }
+
+    /// The README problem: `u(0) = u_t(0) = exp(-40 r^2)`.
+    pub fn initialize(&mut self) {
+        let gaussian = InitialCondition::default();
+        self.initialize_from(&gaussian, &gaussian);
+    }
+
+    /// Radial profiles, evaluated at the one-dimensional point `[r]`.
+    pub fn initialize_from(
+        &mut self,
+        displacement: &InitialCondition,
+        velocity: &InitialCondition,
+    ) {
+        self.initialize_with(|r| displacement.evaluate(&[r]), |r| velocity.evaluate(&[r]));
+    }
+
+    /// Sets `u_2 = u(0)` and `u_1 = u(delta_t)` from the second-order Taylor
+    /// start, like [`GridND::initialize_with`](crate::GridND::initialize_with).
+    pub fn initialize_with(
+        &mut self,
+        displacement: impl Fn(f64) -> f64,
+        velocity: impl Fn(f64) -> f64,
+    ) {
+        for i in 0..self.num_points {
+            self.u_2[i] = displacement(self.coordinate(i));
+        }
+        impose_dirichlet(self.boundary, &mut self.u_2);
+        let half = 0.5 * self.delta_t * self.delta_t;
+        for i in 0..self.num_points {
+            self.u_1[i] = if self.is_imposed(i) {
+                self.u_2[i]
+            } else {
+                self.u_2[i]
+                    + self.delta_t * velocity(self.coordinate(i))
+                    + half * self.laplacian(&self.u_2, i)
+            };
+        }
+        impose_dirichlet(self.boundary, &mut self.u_1);
+        self.time = self.delta_t;
+    }
+
+    pub fn step(&mut self) {
+        let dt2 = self.delta_t * self.delta_t;
+        let last = self.num_points - 1;
+        let (u, u_old) = (&self.u_1, &self.u_2);
+        let points = self.tmp[1..last]
+            .iter_mut()
+            .zip(u.windows(3))
+            .zip(u_old[1..last].iter())
+            .zip(self.outer[1..last].iter().zip(self.inner[1..last].iter()));
+        for (((new, w), old), (outer, inner)) in points {
+            *new = 2.0 * w[1] - old + dt2 * (outer * (w[2] - w[1]) - inner * (w[1] - w[0]));
+        }
+        for i in [0, last] {
+            if !self.is_imposed(i) {
+                self.tmp[i] = 2.0 * u[i] - u_old[i] + dt2 * self.laplacian(u, i);
+            }
+        }
+        match self.boundary {
+            BoundaryCondition::Dirichlet { value } => self.tmp[last] = value,
+            BoundaryCondition::Absorbing { .. } => {
+                let ratio = self.delta_t / self.delta_r;
+                let k = (ratio - 1.0) / (ratio + 1.0);
+                let weight = |i: usize| self.coordinate(i).powf(0.5 * (self.dim - 1.0));
+                let (w, w_in) = (weight(last), weight(last - 1));
+                self.tmp[last] = (w_in * self.u_1[last - 1]
+                    + k * (w_in * self.tmp[last - 1] - w * self.u_1[last]))
+                    / w;
+            }
+            BoundaryCondition::Neumann | BoundaryCondition::Periodic => {}
+        }
+        std::mem::swap(&mut self.u_2, &mut self.u_1);
+        std::mem::swap(&mut self.u_1, &mut self.tmp);
+        self.time += self.delta_t;
+    }
+
+    /// Linear interpolation of `u_1` at radius `r`, which is clamped to the grid.
+    pub fn value_at(&self, r: f64) -> f64 {
+        let s = (r / self.delta_r).clamp(0.0, (self.num_points - 1) as f64);
+        let i = (s as usize).min(self.num_points - 2);
+        let t = s - i as f64;
+        (1.0 - t) * self.u_1[i] + t * self.u_1[i + 1]
+    }
+
+    /// Discrete energy per unit solid angle, `½ |(u_1 - u_2) / dt|^2 - ½ <u_1, L u_2>`
+    /// weighted by the cell volumes; multiplied by the area of the unit sphere it
+    /// matches [`GridND::energy`](crate::GridND::energy). Leapfrog conserves it
+    /// exactly unless the outer boundary absorbs.
+    pub fn energy(&self) -> f64 {
+        self.energy_within(f64::INFINITY)
+    }
+
+    /// Part of [`RadialGrid::energy`] in the points with `r <= radius`, with the
+    /// gradient term summed by parts into `½ Σ r_{i+1/2}^{N-1} Δu_1 Δu_2 / dr`
+    /// over the faces between them, so that it has no surface term at `radius`.
+    /// The sum over every point equals the energy for a homogeneous outer condition.
+    pub fn energy_within(&self, radius: f64) -> f64 {
+        let inside = (0..self.num_points)
+            .take_while(|&i| self.coordinate(i) <= radius)
+            .count();
+        let kinetic: f64 = (0..inside)
+            .filter(|&i| !self.is_imposed(i))
+            .map(|i| {
+                let rate = (self.u_1[i] - self.u_2[i]) / self.delta_t;
+                0.5 * self.volume[i] * rate * rate
+            })
+            .sum();
+        let gradient: f64 = (0..inside.saturating_sub(1))
+            .map(|i| {
+                let (d_1, d_2) = (self.u_1[i + 1] - self.u_1[i], self.u_2[i + 1] - self.u_2[i]);
+                0.5 * self.outer[i] * self.volume[i] * d_1 * d_2
+            })
+            .sum();
+        kinetic + gradient
+    }
+
+    /// Whether the outer point is set by the boundary instead of the scheme.
+    fn is_imposed(&self, i: usize) -> bool {
+        i == self.num_points - 1
+            && matches!(
+                self.boundary,
+                BoundaryCondition::Dirichlet { .. } | BoundaryCondition::Absorbing { .. }
+            )
+    }
+
+    /// `u_rr + (N - 1) / r u_r` at point `i` in conservative form; beyond a
+    /// Neumann outer boundary the flux vanishes.
+    fn laplacian(&self, u: &[f64], i: usize) -> f64 {
+        let outer = if i + 1 < self.num_points {
+            self.outer[i] * (u[i + 1] - u[i])
+        } else {
+            0.0
+        };
+        let inner = if i > 0 {
+            self.inner[i] * (u[i] - u[i - 1])
+        } else {
+            0.0
+        };
+        outer - inner
+    }
+}
+
+fn impose_dirichlet(boundary: BoundaryCondition, u: &mut [f64]) {
+    if let BoundaryCondition::Dirichlet { value } = boundary {
+        if let Some(last) = u.last_mut() {
+            *last = value;
+        }
+    }
+}
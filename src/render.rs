@@ -0,0 +1,372 @@
+use crate::decay::DecayFit;
+use crate::error::{Error, Result};
+use crate::grid::GridND;
+use crate::profile::RadialBin;
+use apng::{load_dynamic_image, Encoder, Frame, PNGImage};
+use plotters::prelude::*;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Read};
+use std::path::{Path, PathBuf};
+
+/// Line colours of the curves in a chart, reused in order when there are more curves.
+const PALETTE: [RGBColor; 7] = [BLACK, RED, GREEN, BLUE, MAGENTA, CYAN, YELLOW];
+
+/// Path of frame `i` inside `dir`, `{i:04}.png`.
+pub fn frame_path(dir: &Path, i: usize) -> PathBuf {
+    dir.join(format!("{:04}.png", i))
+}
+
+/// Writes frame `i` into `dir`: one center-line plot per axis of `grid`.
+pub fn draw(grid: &GridND, dir: &Path, i: usize) -> Result<()> {
+    let out_file_name = frame_path(dir, i);
+
+    let root_area = BitMapBackend::new(&out_file_name, (2560, 1440)).into_drawing_area();
+
+    root_area.fill(&WHITE).map_err(failed)?;
+
+    let equation = (0..grid.ndim)
+        .map(|a| format!("u_{0}{0}", axis_name(a)))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let root_area = root_area
+        .titled(
+            &format!("{}D, u_tt = {}.", grid.ndim, equation),
+            ("sans-serif", 100),
+        )
+        .map_err(failed)?;
+
+    let rows = grid.ndim.max(4).div_ceil(2);
+    let drawing_areas = root_area.split_evenly((rows, 2));
+
+    for (a, area) in drawing_areas.iter().enumerate() {
+        let caption = if a < grid.ndim {
+            axis_name(a)
+        } else {
+            String::new()
+        };
+        let mut cc = ChartBuilder::on(area)
+            .x_label_area_size(30)
+            .y_label_area_size(30)
+            .margin_right(20)
+            .caption(caption, ("sans-serif", 60))
+            .build_cartesian_2d(grid.lower as f32..grid.upper as f32, -1f32..1f32)
+            .map_err(failed)?;
+        cc.configure_mesh()
+            .x_labels(5)
+            .y_labels(3)
+            .max_light_lines(4)
+            .draw()
+            .map_err(failed)?;
+
+        if a < grid.ndim {
+            cc.draw_series(LineSeries::new(
+                grid.center_line(a)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, u)| (grid.coordinate(i) as f32, u as f32)),
+                &BLUE,
+            ))
+            .map_err(failed)?;
+        }
+    }
+    root_area.present().map_err(failed)?;
+    Ok(())
+}
+
+pub(crate) fn axis_name(axis: usize) -> String {
+    match axis {
+        0 => "x".to_string(),
+        1 => "y".to_string(),
+        2 => "z".to_string(),
+        3 => "w".to_string(),
+        _ => format!("x{}", axis + 1),
+    }
+}
+
+/// Writes frame `i` into `dir`: the x center lines of all `grids` on one chart.
+pub fn draw_comparison(grids: &[&GridND], dir: &Path, i: usize) -> Result<()> {
+    let out_file_name = frame_path(dir, i);
+
+    let root = BitMapBackend::new(&out_file_name, (2560, 1440)).into_drawing_area();
+    let lower = grids.iter().map(|g| g.lower).fold(f64::INFINITY, f64::min);
+    let upper = grids
+        .iter()
+        .map(|g| g.upper)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    root.fill(&WHITE).map_err(failed)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .build_cartesian_2d(lower..upper, -1.0..1.0)
+        .map_err(failed)?;
+
+    chart.configure_mesh().draw().map_err(failed)?;
+
+    for (grid, color) in grids.iter().zip(PALETTE.iter().cycle()) {
+        chart
+            .draw_series(LineSeries::new(
+                grid.center_line(0)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, u)| (grid.coordinate(i), u)),
+                color,
+            ))
+            .map_err(failed)?;
+    }
+
+    root.present().map_err(failed)?;
+    Ok(())
+}
+
+/// Writes frame `i` into `dir` from the radial profiles of `grids`: the mean
+/// `u(r)` of every shell between its minimum and maximum on top, and the mean
+/// scaled by `r^{(N-1)/2}`, which stays level for a freely spreading front,
+/// below. Only shells inside the inscribed sphere of the domain are drawn.
+pub fn draw_profiles(
+    grids: &[&GridND],
+    profiles: &[Vec<RadialBin>],
+    dir: &Path,
+    i: usize,
+) -> Result<()> {
+    let out_file_name = frame_path(dir, i);
+
+    let root = BitMapBackend::new(&out_file_name, (2560, 1440)).into_drawing_area();
+    let reach = grids
+        .iter()
+        .map(|g| g.upper.min(-g.lower))
+        .fold(f64::INFINITY, f64::min);
+    let time = grids.first().map_or(0.0, |g| g.time);
+
+    root.fill(&WHITE).map_err(failed)?;
+    let root = root
+        .titled(&format!("t = {:.3}", time), ("sans-serif", 60))
+        .map_err(failed)?;
+    let (upper, lower) = root.split_vertically(root.dim_in_pixel().1 / 2);
+
+    let scaled =
+        |grid: &GridND, bin: &RadialBin| bin.mean * bin.radius.powf(0.5 * (grid.ndim as f64 - 1.0));
+    let largest = grids
+        .iter()
+        .zip(profiles)
+        .flat_map(|(g, bins)| bins.iter().map(move |b| scaled(g, b).abs()))
+        .fold(1e-3, f64::max);
+
+    let mut chart = ChartBuilder::on(&upper)
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .caption("u(r)", ("sans-serif", 40))
+        .build_cartesian_2d(0.0..reach, -1.0..1.0)
+        .map_err(failed)?;
+    chart.configure_mesh().draw().map_err(failed)?;
+    for ((grid, bins), &color) in grids.iter().zip(profiles).zip(PALETTE.iter().cycle()) {
+        let inside = || bins.iter().filter(|b| b.radius <= reach);
+        for edge in [|b: &RadialBin| b.min, |b: &RadialBin| b.max] {
+            chart
+                .draw_series(LineSeries::new(
+                    inside().map(|b| (b.radius, edge(b))),
+                    color.mix(0.3),
+                ))
+                .map_err(failed)?;
+        }
+        chart
+            .draw_series(LineSeries::new(
+                inside().map(|b| (b.radius, b.mean)),
+                color.stroke_width(2),
+            ))
+            .map_err(failed)?
+            .label(format!("{}D", grid.ndim))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 40, y)], color));
+    }
+    chart
+        .configure_series_labels()
+        .label_font(("sans-serif", 40))
+        .background_style(WHITE)
+        .border_style(BLACK)
+        .draw()
+        .map_err(failed)?;
+
+    let mut chart = ChartBuilder::on(&lower)
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .caption("r^((N-1)/2) u(r)", ("sans-serif", 40))
+        .build_cartesian_2d(0.0..reach, -largest..largest)
+        .map_err(failed)?;
+    chart.configure_mesh().draw().map_err(failed)?;
+    for ((grid, bins), &color) in grids.iter().zip(profiles).zip(PALETTE.iter().cycle()) {
+        chart
+            .draw_series(LineSeries::new(
+                bins.iter()
+                    .filter(|b| b.radius <= reach)
+                    .map(|b| (b.radius, scaled(grid, b))),
+                color.stroke_width(2),
+            ))
+            .map_err(failed)?;
+    }
+
+    root.present().map_err(failed)?;
+    Ok(())
+}
+
+/// Assembles the first `num` frames in `dir` into the animated PNG `out_file_name`.
+pub fn gen_apng(dir: &Path, num: usize, out_file_name: &Path) -> Result<()> {
+    let mut png_images: Vec<PNGImage> = Vec::new();
+
+    for i in 0..num {
+        let path = frame_path(dir, i);
+        let in_frame =
+            |err: &dyn fmt::Display| Error::Render(format!("{}: {}", path.display(), err));
+        let mut buffer = vec![];
+        File::open(&path)
+            .and_then(|mut file| file.read_to_end(&mut buffer))
+            .map_err(|e| in_frame(&e))?;
+        let img = image::load_from_memory(&buffer).map_err(|e| in_frame(&e))?;
+        png_images.push(load_dynamic_image(img).map_err(|e| in_frame(&e))?);
+    }
+
+    let mut out = BufWriter::new(File::create(out_file_name)?);
+
+    let config = apng::create_config(&png_images, None).map_err(failed)?;
+    let mut encoder = Encoder::new(&mut out, config).map_err(failed)?;
+
+    for image in png_images.iter() {
+        let frame = Frame {
+            delay_num: Some(1),
+            delay_den: Some(20),
+            ..Default::default()
+        };
+        encoder.write_frame(image, frame).map_err(failed)?;
+    }
+
+    encoder.finish_encode().map_err(failed)?;
+    Ok(())
+}
+
+/// Wraps an error of the plotting or image crates.
+fn failed(err: impl fmt::Display) -> Error {
+    Error::Render(err.to_string())
+}
+
+/// Draws the time series `(t, u)` of several labelled signals, each divided by
+/// its largest `|u|`, on one chart written to `out_file_name`.
+pub fn draw_signals(signals: &[(String, Vec<(f64, f64)>)], out_file_name: &Path) -> Result<()> {
+    let root = BitMapBackend::new(out_file_name, (2560, 1440)).into_drawing_area();
+    let end = signals
+        .iter()
+        .filter_map(|(_, s)| s.last().map(|(t, _)| *t))
+        .fold(0.0, f64::max);
+
+    root.fill(&WHITE).map_err(failed)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .build_cartesian_2d(0.0..end, -1.0..1.0)
+        .map_err(failed)?;
+
+    chart.configure_mesh().draw().map_err(failed)?;
+
+    for ((label, signal), &color) in signals.iter().zip(PALETTE.iter().cycle()) {
+        let peak = signal.iter().map(|(_, u)| u.abs()).fold(0.0, f64::max);
+        let scale = if peak > 0.0 { peak.recip() } else { 1.0 };
+        chart
+            .draw_series(LineSeries::new(
+                signal.iter().map(|&(t, u)| (t, u * scale)),
+                color,
+            ))
+            .map_err(failed)?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 40, y)], color));
+    }
+    chart
+        .configure_series_labels()
+        .label_font(("sans-serif", 40))
+        .background_style(WHITE)
+        .border_style(BLACK)
+        .draw()
+        .map_err(failed)?;
+
+    root.present().map_err(failed)?;
+    Ok(())
+}
+
+/// Draws the front peaks of every fit from `start / 4` on, with their fitted
+/// power laws over `t >= start`, on log-log axes written to `out_file_name`.
+pub fn draw_decay(fits: &[DecayFit], start: f64, out_file_name: &Path) -> Result<()> {
+    let root = BitMapBackend::new(out_file_name, (2560, 1440)).into_drawing_area();
+    let curves: Vec<Vec<(f64, f64)>> = fits
+        .iter()
+        .map(|fit| {
+            fit.peaks
+                .iter()
+                .copied()
+                .filter(|&(t, peak)| t >= start / 4.0 && peak > 0.0)
+                .collect()
+        })
+        .collect();
+    let (mut end, mut low, mut high) = (start, f64::INFINITY, f64::MIN_POSITIVE);
+    for &(t, peak) in curves.iter().flatten() {
+        end = end.max(t);
+        low = low.min(peak);
+        high = high.max(peak);
+    }
+    if low >= high {
+        low = high;
+    }
+    let (low, high) = (0.5 * low, 2.0 * high);
+
+    root.fill(&WHITE).map_err(failed)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .margin(5)
+        .caption("peak of the front against t", ("sans-serif", 60))
+        .x_label_area_size(60)
+        .y_label_area_size(100)
+        .build_cartesian_2d((start / 4.0..end).log_scale(), (low..high).log_scale())
+        .map_err(failed)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("t")
+        .y_desc("max |u|")
+        .label_style(("sans-serif", 30))
+        .draw()
+        .map_err(failed)?;
+
+    for ((fit, curve), &color) in fits.iter().zip(curves).zip(PALETTE.iter().cycle()) {
+        chart
+            .draw_series(LineSeries::new(curve, color.stroke_width(3)))
+            .map_err(failed)?
+            .label(format!(
+                "{}D, p = {:.3} ({:.1} expected)",
+                fit.dim, fit.fit.exponent, fit.expected
+            ))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 40, y)], color));
+        let law = fit.fit;
+        chart
+            .draw_series(LineSeries::new(
+                [start, end]
+                    .into_iter()
+                    .map(|t| (t, law.prefactor * t.powf(-law.exponent))),
+                color.mix(0.4),
+            ))
+            .map_err(failed)?;
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::LowerLeft)
+        .label_font(("sans-serif", 40))
+        .background_style(WHITE)
+        .border_style(BLACK)
+        .draw()
+        .map_err(failed)?;
+
+    root.present().map_err(failed)?;
+    Ok(())
+}
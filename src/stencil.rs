@@ -0,0 +1,162 @@
+//! Central-difference approximations of the second derivative.
+
+use crate::boundary::Boundaries;
+use crate::error::{Error, Result};
+use crate::field::Field;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Order of accuracy of the Laplacian used by the scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stencil {
+    /// The 3-point stencil `[1, -2, 1]`.
+    #[default]
+    Second,
+    Fourth,
+    Sixth,
+    Eighth,
+}
+
+impl Stencil {
+    pub const ALL: [Stencil; 4] = [
+        Stencil::Second,
+        Stencil::Fourth,
+        Stencil::Sixth,
+        Stencil::Eighth,
+    ];
+
+    /// Weights `c_0, c_1, ..., c_r`, times `delta_x^2`: `c_0` of the centre `u_i`
+    /// and `c_k` of both `u_{i-k}` and `u_{i+k}`.
+    pub fn coefficients(self) -> &'static [f64] {
+        match self {
+            Stencil::Second => &[-2.0, 1.0],
+            Stencil::Fourth => &[-5.0 / 2.0, 4.0 / 3.0, -1.0 / 12.0],
+            Stencil::Sixth => &[-49.0 / 18.0, 3.0 / 2.0, -3.0 / 20.0, 1.0 / 90.0],
+            Stencil::Eighth => &[
+                -205.0 / 72.0,
+                8.0 / 5.0,
+                -1.0 / 5.0,
+                8.0 / 315.0,
+                -1.0 / 560.0,
+            ],
+        }
+    }
+
+    pub fn order(self) -> usize {
+        2 * self.radius()
+    }
+
+    /// Number of neighbours on each side of the centre point.
+    pub fn radius(self) -> usize {
+        self.coefficients().len() - 1
+    }
+
+    /// Largest magnitude of the stencil's symbol, reached by the grid-scale
+    /// mode `(-1)^i`; the leapfrog limit is `delta_t <= 2 delta_x / sqrt(N * symbol_max)`.
+    pub fn symbol_max(self) -> f64 {
+        let c = self.coefficients();
+        let alternating: f64 = c[1..]
+            .iter()
+            .enumerate()
+            .map(|(k, c)| if k % 2 == 0 { -c } else { *c })
+            .sum();
+        -(c[0] + 2.0 * alternating)
+    }
+
+    /// `delta_x^2` times the Laplacian of `u` at a point `p` at least
+    /// [`Stencil::radius`] points away from every face.
+    pub fn interior_sum(self, u: &Field, p: usize) -> f64 {
+        let c = self.coefficients();
+        let mut lap = u.ndim() as f64 * c[0] * u[p];
+        for &s in u.strides.iter() {
+            for (k, c) in c.iter().enumerate().skip(1) {
+                lap += c * (u[p + k * s] + u[p - k * s]);
+            }
+        }
+        lap
+    }
+
+    /// `delta_x^2` times the second derivative of `u` along `axis` at the
+    /// point `p` with index `i` along that axis, reading points beyond the
+    /// faces as ghosts given by `boundaries`.
+    pub fn second_difference(
+        self,
+        u: &Field,
+        boundaries: &Boundaries,
+        p: usize,
+        axis: usize,
+        i: usize,
+    ) -> f64 {
+        let c = self.coefficients();
+        let j = i as isize;
+        let mut sum = c[0] * u[p];
+        for (k, c) in c.iter().enumerate().skip(1) {
+            let k = k as isize;
+            sum += c
+                * (boundaries.neighbor(u, p, axis, i, j - k)
+                    + boundaries.neighbor(u, p, axis, i, j + k));
+        }
+        sum
+    }
+
+    /// `delta_x` times the derivative of `u` along `axis` at the half point
+    /// between the indices `i` and `i + direction` (`direction` being `±1`),
+    /// seen from the point `p` with index `i`, with ghosts as in
+    /// [`Stencil::second_difference`].
+    ///
+    /// It is `Σ_m g_m (u_{i+(m+1)d} - u_{i-md})` with `g_m = c_{m+1} + ... + c_r`,
+    /// so the difference of two neighbouring half points is exactly
+    /// [`Stencil::second_difference`].
+    pub fn half_difference(
+        self,
+        u: &Field,
+        boundaries: &Boundaries,
+        p: usize,
+        axis: usize,
+        i: usize,
+        direction: isize,
+    ) -> f64 {
+        let c = self.coefficients();
+        let j = i as isize;
+        let mut sum = 0.0;
+        for m in 0..self.radius() {
+            let g: f64 = c[m + 1..].iter().sum();
+            let m = m as isize;
+            sum += g
+                * (boundaries.neighbor(u, p, axis, i, j + (m + 1) * direction)
+                    - boundaries.neighbor(u, p, axis, i, j - m * direction));
+        }
+        sum
+    }
+}
+
+impl fmt::Display for Stencil {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Stencil::Second => "second",
+            Stencil::Fourth => "fourth",
+            Stencil::Sixth => "sixth",
+            Stencil::Eighth => "eighth",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Stencil {
+    type Err = Error;
+
+    /// Accepts the configuration names (`"fourth"`) as well as the order (`"4"`).
+    fn from_str(s: &str) -> Result<Self> {
+        Stencil::ALL
+            .into_iter()
+            .find(|stencil| stencil.to_string() == s || stencil.order().to_string() == s)
+            .ok_or_else(|| {
+                Error::InvalidConfig(format!(
+                    "unknown stencil {:?}, expected second, fourth, sixth or eighth",
+                    s
+                ))
+            })
+    }
+}
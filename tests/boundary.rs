@@ -0,0 +1,93 @@
+use wave_equation_difference_between_3d_and_4d::{
+    AbsorbingOrder, Boundaries, BoundaryCondition, Field, GridND,
+};
+
+/// Every value on every face of `u`.
+fn face_values(u: &Field) -> Vec<f64> {
+    let mut values = Vec::new();
+    for axis in 0..u.ndim() {
+        for i in [0, u.shape[axis] - 1] {
+            values.extend(u.face_points(axis, i).map(|p| u[p]));
+        }
+    }
+    values
+}
+
+#[test]
+fn homogeneous_dirichlet_holds_on_all_faces() {
+    for ndim in 1..=4 {
+        let mut grid = GridND::new(ndim, 9);
+        grid.initialize();
+        for _ in 0..40 {
+            grid.step();
+            for u in [&grid.x_1, &grid.x_2] {
+                assert!(face_values(u).iter().all(|&v| v == 0.0), "{}D", ndim);
+            }
+        }
+        assert!(grid.x_1.data.iter().any(|&v| v != 0.0));
+    }
+}
+
+#[test]
+fn inhomogeneous_dirichlet_holds_on_all_faces() {
+    let mut grid = GridND::new(3, 11);
+    grid.boundaries = Boundaries::dirichlet(3, 0.25);
+    grid.initialize();
+    for _ in 0..40 {
+        grid.step();
+        for u in [&grid.x_1, &grid.x_2] {
+            assert!(face_values(u).iter().all(|&v| v == 0.25));
+        }
+    }
+}
+
+/// The README pulse on a grid of `size` points with absorbing faces of
+/// `order` on every side.
+fn absorbing_grid(ndim: usize, size: usize, order: AbsorbingOrder) -> GridND {
+    let mut grid = GridND::new(ndim, size);
+    grid.boundaries = Boundaries::uniform(ndim, BoundaryCondition::Absorbing { order });
+    grid.initialize();
+    grid
+}
+
+#[test]
+fn absorbing_faces_let_the_pulse_leave() {
+    for ndim in [2, 3] {
+        let remaining = [AbsorbingOrder::First, AbsorbingOrder::Second].map(|order| {
+            let mut grid = absorbing_grid(ndim, 33, order);
+            let initial = grid.energy();
+            while grid.time < 3.0 {
+                grid.step();
+            }
+            grid.energy() / initial
+        });
+        assert!(
+            remaining[0] < 2e-3 && remaining[1] < remaining[0],
+            "{}D: first order leaves {:e}, second order {:e}",
+            ndim,
+            remaining[0],
+            remaining[1]
+        );
+    }
+}
+
+#[test]
+fn absorbing_faces_stay_bounded() {
+    for order in [AbsorbingOrder::First, AbsorbingOrder::Second] {
+        let mut grid = absorbing_grid(2, 33, order);
+        let initial = grid.energy();
+        let mut largest = 0.0_f64;
+        while grid.time < 100.0 {
+            grid.step();
+            largest = largest.max(grid.x_1.data.iter().fold(0.0, |m, u| m.max(u.abs())));
+        }
+        assert!(largest <= 1.0, "{:?}: |u| reached {}", order, largest);
+        let remaining = grid.energy() / initial;
+        assert!(
+            remaining.abs() < 1e-6,
+            "{:?}: {:e} of the energy left",
+            order,
+            remaining
+        );
+    }
+}
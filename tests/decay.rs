@@ -0,0 +1,46 @@
+use wave_equation_difference_between_3d_and_4d::{Decay, PowerLaw, RadialPulse, SimulationConfig};
+
+#[test]
+fn power_law_fit_recovers_exponent() {
+    let samples: Vec<(f64, f64)> = (1..=20)
+        .map(|i| {
+            let t = i as f64 * 0.5;
+            (t, 3.0 * t.powf(-1.5))
+        })
+        .collect();
+    let law = PowerLaw::fit(&samples).unwrap();
+    assert!((law.exponent - 1.5).abs() < 1e-12, "{:?}", law);
+    assert!((law.prefactor - 3.0).abs() < 1e-12, "{:?}", law);
+    assert!((law.r_squared - 1.0).abs() < 1e-12, "{:?}", law);
+    assert_eq!(PowerLaw::fit(&samples[..1]), None);
+}
+
+#[test]
+fn front_decays_like_the_sphere_area() {
+    let config = SimulationConfig::default();
+    let report = Decay {
+        pulse: RadialPulse::new(
+            config.displacement,
+            config.velocity,
+            1.0,
+            4001,
+            config.courant,
+        ),
+        start: 2.0,
+        until: 6.0,
+    };
+    let fits: Vec<_> = [3.0, 4.0]
+        .into_iter()
+        .map(|dim| report.measure(dim).unwrap())
+        .collect();
+    for fit in &fits {
+        assert!(
+            (fit.fit.exponent - fit.expected).abs() < 0.05,
+            "{}D: {} vs {}",
+            fit.dim,
+            fit.fit.exponent,
+            fit.expected
+        );
+    }
+    assert!(fits[1].fit.exponent > fits[0].fit.exponent + 0.4);
+}
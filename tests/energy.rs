@@ -0,0 +1,53 @@
+use wave_equation_difference_between_3d_and_4d::{
+    Boundaries, BoundaryCondition, GridND, Side, Stencil,
+};
+
+#[test]
+fn leapfrog_conserves_energy() {
+    let conditions = [
+        BoundaryCondition::Dirichlet { value: 0.0 },
+        BoundaryCondition::Dirichlet { value: 0.25 },
+        BoundaryCondition::Neumann,
+        BoundaryCondition::Periodic,
+    ];
+    for ndim in 1..=3 {
+        for stencil in Stencil::ALL {
+            for condition in conditions {
+                let mut grid = GridND::new(ndim, 17);
+                grid.boundaries = Boundaries::uniform(ndim, condition);
+                if condition == (BoundaryCondition::Dirichlet { value: 0.25 }) {
+                    // The lift must cancel next to Neumann faces too.
+                    grid.boundaries
+                        .set(0, Side::Upper, BoundaryCondition::Neumann);
+                }
+                grid.set_stencil(stencil);
+                grid.initialize();
+                let initial = grid.energy();
+                assert!(initial > 0.0);
+                // Long enough for the pulse to reach every face several times.
+                for _ in 0..400 {
+                    grid.step();
+                    let drift = (grid.energy() - initial) / initial;
+                    assert!(
+                        drift.abs() < 1e-12,
+                        "{}D, {}, {:?}: drift {:e} at t = {}",
+                        ndim,
+                        stencil,
+                        condition,
+                        drift,
+                        grid.time
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn energy_of_a_field_at_rest_on_its_dirichlet_value_vanishes() {
+    let mut grid = GridND::new(2, 17);
+    grid.boundaries = Boundaries::dirichlet(2, 0.25);
+    grid.initialize_with(|_| 0.25, |_| 0.0);
+    grid.step();
+    assert_eq!(grid.energy(), 0.0);
+}
@@ -0,0 +1,67 @@
+use wave_equation_difference_between_3d_and_4d::{Analytic, RadialGrid};
+
+/// `u` at the origin once the README pulse has passed, `t = 1`.
+fn tail(dim: usize) -> f64 {
+    Analytic::readme(dim).evaluate(0.0, 1.0)
+}
+
+#[test]
+fn odd_dimensions_leave_no_tail() {
+    for dim in [1, 3, 5] {
+        let expected = if dim == 1 {
+            // d'Alembert: half the integral of the initial velocity stays behind.
+            0.5 * (std::f64::consts::PI / 40.0).sqrt()
+        } else {
+            0.0
+        };
+        assert!(
+            (tail(dim) - expected).abs() < 1e-10,
+            "{}D: {}",
+            dim,
+            tail(dim)
+        );
+    }
+}
+
+#[test]
+fn even_dimensions_leave_a_tail() {
+    for dim in [2, 4] {
+        assert!(tail(dim).abs() > 1e-4, "{}D: {}", dim, tail(dim));
+    }
+}
+
+#[test]
+fn kirchhoff_matches_closed_form() {
+    let f = |r: f64| (-40.0 * r * r).exp();
+    let (r, t) = (0.3, 0.8);
+    // r u = ((r - t) f(r - t) + (r + t) f(r + t)) / 2 + 1/2 ∫_{r-t}^{r+t} s f(s) ds
+    let exact =
+        ((r - t) * f(r - t) + (r + t) * f(r + t) + (f(r - t) - f(r + t)) / 80.0) / (2.0 * r);
+    let u = Analytic::readme(3).evaluate(r, t);
+    assert!((u - exact).abs() < 1e-10, "{} vs {}", u, exact);
+}
+
+#[test]
+fn radial_solver_converges_to_analytic() {
+    for dim in [2, 4] {
+        let analytic = Analytic::readme(dim);
+        let mut radial = RadialGrid::new(dim as f64, 2001, 2.0);
+        let steps = (0.8 / radial.delta_t).ceil();
+        radial.set_time_step(0.8 / steps).unwrap();
+        radial.initialize();
+        for _ in 1..steps as usize {
+            radial.step();
+        }
+        for r in [0.0, 0.3, 0.8] {
+            let (u, exact) = (radial.value_at(r), analytic.evaluate(r, 0.8));
+            assert!(
+                (u - exact).abs() < 1e-4,
+                "{}D, r = {}: {} vs {}",
+                dim,
+                r,
+                u,
+                exact
+            );
+        }
+    }
+}
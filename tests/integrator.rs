@@ -0,0 +1,93 @@
+use std::f64::consts::PI;
+use wave_equation_difference_between_3d_and_4d::{GridND, TimeIntegrator};
+
+/// Largest error at `t = 1/2` of the standing wave `sin(π (x + 1)) cos(ω t)` on
+/// 33 points of `[-1, 1]`, run in `steps` steps. `ω` is the frequency of the
+/// mode under the 3-point Laplacian, so only the time stepping errs, and at
+/// `ω t ≈ π / 2` a phase error shows in full.
+fn standing_wave_error(integrator: TimeIntegrator, steps: usize) -> f64 {
+    let mut grid = GridND::new(1, 33);
+    grid.set_integrator(integrator).unwrap();
+    grid.set_time_step(0.5 / steps as f64).unwrap();
+    let mode = |x: f64| (PI * (x + 1.0)).sin();
+    grid.initialize_with(|x| mode(x[0]), |_| 0.0);
+    while grid.time < 0.5 - 0.5 * grid.delta_t {
+        grid.step();
+    }
+    let omega = 2.0 / grid.delta_x * (0.5 * PI * grid.delta_x).sin();
+    (0..grid.num_grid)
+        .map(|i| (grid.x_1[i] - mode(grid.coordinate(i)) * (omega * grid.time).cos()).abs())
+        .fold(0.0, f64::max)
+}
+
+#[test]
+fn integrators_converge_at_their_order() {
+    let expected = [
+        (TimeIntegrator::Leapfrog, 2.0),
+        (TimeIntegrator::Verlet, 2.0),
+        (TimeIntegrator::Rk4, 4.0),
+        (TimeIntegrator::ModifiedEquation, 4.0),
+    ];
+    for (integrator, order) in expected {
+        let coarse = standing_wave_error(integrator, 16);
+        let fine = standing_wave_error(integrator, 32);
+        let observed = (coarse / fine).log2();
+        assert!(
+            (observed - order).abs() < 0.2,
+            "{}: errors {:e}, {:e}, observed order {}",
+            integrator,
+            coarse,
+            fine,
+            observed
+        );
+    }
+}
+
+/// Largest `|u|` of the README pulse on a 2D grid after 400 steps at
+/// `ratio` times the integrator's limit.
+fn largest_after(integrator: TimeIntegrator, ratio: f64) -> f64 {
+    let mut grid = GridND::new(2, 33);
+    grid.set_integrator(integrator).unwrap();
+    grid.delta_t = ratio * grid.max_time_step();
+    grid.initialize();
+    for _ in 0..400 {
+        grid.step();
+    }
+    grid.x_1.data.iter().fold(0.0, |m, u| m.max(u.abs()))
+}
+
+#[test]
+fn integrators_are_stable_up_to_their_limit() {
+    let factors = [
+        (TimeIntegrator::Leapfrog, 1.0),
+        (TimeIntegrator::Verlet, 1.0),
+        (TimeIntegrator::Rk4, 2f64.sqrt()),
+        (TimeIntegrator::ModifiedEquation, 3f64.sqrt()),
+    ];
+    for (integrator, factor) in factors {
+        let mut grid = GridND::new(2, 33);
+        let leapfrog = grid.max_time_step();
+        grid.set_integrator(integrator).unwrap();
+        assert!(
+            (grid.max_time_step() - factor * leapfrog).abs() < 1e-15,
+            "{}",
+            integrator
+        );
+        assert!(grid.set_courant(1.01).is_err(), "{}", integrator);
+
+        let below = largest_after(integrator, 0.99);
+        assert!(
+            below < 2.0,
+            "{} below the limit: |u| = {}",
+            integrator,
+            below
+        );
+        let above = largest_after(integrator, 1.05);
+        assert!(
+            above > 1e3,
+            "{} above the limit: |u| = {}",
+            integrator,
+            above
+        );
+    }
+}
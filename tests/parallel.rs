@@ -0,0 +1,33 @@
+use wave_equation_difference_between_3d_and_4d::{
+    BoundaryCondition, GridND, Side, Stencil, TimeIntegrator,
+};
+
+/// `x_1` after 40 steps of a 3D grid with a Neumann face, run in a pool of
+/// `threads` threads.
+fn run(threads: usize, integrator: TimeIntegrator) -> Vec<f64> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap();
+    pool.install(|| {
+        let mut grid = GridND::new(3, 33);
+        grid.boundaries
+            .set(0, Side::Lower, BoundaryCondition::Neumann);
+        grid.set_stencil(Stencil::Fourth);
+        grid.set_integrator(integrator).unwrap();
+        grid.initialize();
+        for _ in 0..40 {
+            grid.step();
+        }
+        grid.x_1.data
+    })
+}
+
+#[test]
+fn results_do_not_depend_on_thread_count() {
+    for integrator in [TimeIntegrator::Leapfrog, TimeIntegrator::Rk4] {
+        let serial = run(1, integrator);
+        assert!(serial.iter().any(|&u| u != 0.0));
+        assert_eq!(serial, run(4, integrator), "{}", integrator);
+    }
+}
@@ -0,0 +1,87 @@
+use wave_equation_difference_between_3d_and_4d::{
+    BoundaryCondition, GridND, PmlConfig, Side, Stencil, TimeIntegrator,
+};
+
+/// Energy of a 2D pulse at rest after time 2, relative to its initial
+/// energy, with or without a layer.
+fn remaining_energy(pml: Option<PmlConfig>) -> f64 {
+    let mut grid = GridND::new(2, 65);
+    if let Some(pml) = pml {
+        grid.set_pml(pml).unwrap();
+    }
+    grid.initialize_with(|x| (-40.0 * (x[0] * x[0] + x[1] * x[1])).exp(), |_| 0.0);
+    let initial = grid.energy();
+    while grid.time < 2.0 {
+        grid.step();
+    }
+    grid.energy() / initial
+}
+
+#[test]
+fn pulse_loses_its_energy_in_the_layer() {
+    let closed = remaining_energy(None);
+    assert!((closed - 1.0).abs() < 1e-10, "Dirichlet: {}", closed);
+    let pml = PmlConfig {
+        thickness: 10,
+        ..PmlConfig::default()
+    };
+    let open = remaining_energy(Some(pml));
+    assert!(open < 1e-3, "PML: {} of the energy left", open);
+}
+
+#[test]
+fn measured_reflection_is_small_and_shrinks_with_thickness() {
+    for stencil in Stencil::ALL {
+        let measure = |thickness| {
+            PmlConfig {
+                thickness,
+                ..PmlConfig::default()
+            }
+            .measured_reflection(129, [-1.0, 1.0], stencil, 0.5)
+            .unwrap()
+        };
+        let reflections = [measure(8), measure(16), measure(32)];
+        assert!(reflections[0] < 1e-3, "{}: {:?}", stencil, reflections);
+        assert!(reflections[1] < 1e-4, "{}: {:?}", stencil, reflections);
+        assert!(
+            reflections[2] < 0.5 * reflections[1] && reflections[1] < 0.5 * reflections[0],
+            "{}: {:?}",
+            stencil,
+            reflections
+        );
+    }
+}
+
+#[test]
+fn layer_is_checked_whatever_the_call_order() {
+    let pml = PmlConfig::default();
+    let mut grid = GridND::new(2, 33);
+    grid.set_integrator(TimeIntegrator::Rk4).unwrap();
+    assert!(grid.set_pml(pml).is_err(), "rk4 never applies the layer");
+    assert!(grid.pml.is_none());
+
+    let mut grid = GridND::new(2, 33);
+    grid.set_pml(pml).unwrap();
+    assert!(grid.set_integrator(TimeIntegrator::Rk4).is_err());
+
+    let mut grid = GridND::new(2, 33);
+    for thickness in [0, 17] {
+        let config = PmlConfig {
+            thickness,
+            ..PmlConfig::default()
+        };
+        assert!(grid.set_pml(config).is_err(), "thickness {}", thickness);
+    }
+
+    let mut grid = GridND::new(2, 33);
+    grid.boundaries
+        .set(1, Side::Lower, BoundaryCondition::Periodic);
+    grid.boundaries
+        .set(1, Side::Upper, BoundaryCondition::Periodic);
+    assert!(grid.set_pml(pml).is_err(), "periodic faces");
+    for side in [Side::Lower, Side::Upper] {
+        grid.boundaries.set(0, side, BoundaryCondition::Periodic);
+    }
+    grid.set_spectral().unwrap();
+    assert!(grid.set_pml(pml).is_err(), "spectral solver");
+}
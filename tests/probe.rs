@@ -0,0 +1,45 @@
+use wave_equation_difference_between_3d_and_4d::{GridND, SimulationConfig};
+
+#[test]
+fn interpolation_is_exact_for_multilinear_data() {
+    let f = |x: &[f64]| 0.3 + 0.5 * x[0] - 0.2 * x[1] + 0.1 * x[2] + 0.7 * x[0] * x[1];
+    let mut grid = GridND::new(3, 9);
+    let (mut idx, mut x) = (vec![0; 3], vec![0.0; 3]);
+    for p in 0..grid.x_1.len() {
+        grid.point(p, &mut idx, &mut x);
+        grid.x_1[p] = f(&x);
+    }
+    let points = [
+        vec![0.1, -0.37, 0.52],
+        vec![-1.0, 1.0, 0.999],
+        vec![1.0, 1.0, 1.0],
+        vec![0.6, 0.05],
+        vec![-0.8],
+    ];
+    for point in points {
+        let mut full = point.clone();
+        full.resize(3, 0.0);
+        let (u, exact) = (grid.value_at(&point), f(&full));
+        assert!((u - exact).abs() < 1e-14, "{:?}: {} vs {}", point, u, exact);
+    }
+}
+
+#[test]
+fn probes_outside_the_domain_are_rejected() {
+    let mut config = SimulationConfig {
+        dim: 2,
+        size: 17,
+        ..SimulationConfig::default()
+    };
+    config.output.probes = vec![vec![0.5, -1.0]];
+    config.validate().unwrap();
+    for probe in [
+        vec![1.01],
+        vec![0.0, -1.5],
+        vec![f64::NAN],
+        vec![0.0, 0.0, 0.0],
+    ] {
+        config.output.probes = vec![vec![0.5], probe.clone()];
+        assert!(config.validate().is_err(), "{:?}", probe);
+    }
+}
@@ -0,0 +1,41 @@
+use wave_equation_difference_between_3d_and_4d::{radial_profile, GridND};
+
+#[test]
+fn bins_average_a_radial_function() {
+    let mut grid = GridND::new(2, 33);
+    let (mut idx, mut x) = (vec![0; 2], vec![0.0; 2]);
+    let mut total = 0.0;
+    for p in 0..grid.x_1.len() {
+        grid.point(p, &mut idx, &mut x);
+        grid.x_1[p] = x[0] * x[0] + x[1] * x[1];
+        total += grid.x_1[p];
+    }
+    let width = grid.delta_x;
+    let bins = radial_profile(&grid, width);
+
+    let count: usize = bins.iter().map(|b| b.count).sum();
+    assert_eq!(count, grid.x_1.len());
+    let sum: f64 = bins.iter().map(|b| b.count as f64 * b.mean).sum();
+    assert!((sum - total).abs() < 1e-10 * total, "{} vs {}", sum, total);
+    // The origin is alone in its bin; the next one holds its four neighbours
+    // at r = dx, with r^2 = dx^2, and the four diagonals, with r^2 = 2 dx^2.
+    let h2 = width * width;
+    assert_eq!((bins[0].count, bins[0].mean, bins[0].spread), (1, 0.0, 0.0));
+    assert_eq!(bins[1].count, 8);
+    assert!((bins[1].mean - 1.5 * h2).abs() < 1e-15, "{:?}", bins[1]);
+    assert!((bins[1].spread - 0.5 * h2).abs() < 1e-15, "{:?}", bins[1]);
+    assert_eq!((bins[1].min, bins[1].max), (h2, 2.0 * h2));
+
+    for bin in &bins {
+        let (inner, outer) = (bin.radius - 0.5 * width, bin.radius + 0.5 * width);
+        assert!(
+            inner * inner - 1e-12 <= bin.min
+                && bin.min <= bin.mean
+                && bin.mean <= bin.max
+                && bin.max < outer * outer,
+            "{:?}",
+            bin
+        );
+        assert!(bin.spread <= 0.5 * (bin.max - bin.min) + 1e-12, "{:?}", bin);
+    }
+}
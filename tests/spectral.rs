@@ -0,0 +1,62 @@
+use std::f64::consts::PI;
+use wave_equation_difference_between_3d_and_4d::{
+    Boundaries, BoundaryCondition, GridND, Side, SimulationConfig, Solver,
+};
+
+#[test]
+fn spectral_solver_is_exact_for_standing_waves() {
+    let mut grid = GridND::new(2, 33);
+    grid.boundaries = Boundaries::uniform(2, BoundaryCondition::Periodic);
+    grid.set_spectral().unwrap();
+    grid.set_time_step(0.01).unwrap();
+    // Modes of |k| = √5 π at rest and |k| = 3π moving.
+    let exact = |x: &[f64], t: f64| {
+        (PI * x[0]).cos() * (2.0 * PI * x[1]).cos() * (5f64.sqrt() * PI * t).cos()
+            + (3.0 * PI * x[0]).sin() * (3.0 * PI * t).sin() / (3.0 * PI)
+    };
+    grid.initialize_with(|x| exact(x, 0.0), |x| (3.0 * PI * x[0]).sin());
+    let (mut idx, mut x) = (vec![0; 2], vec![0.0; 2]);
+    for _ in 0..5 {
+        for _ in 0..37 {
+            grid.step();
+        }
+        for p in 0..grid.x_1.len() {
+            grid.point(p, &mut idx, &mut x);
+            let error = (grid.x_1[p] - exact(&x, grid.time)).abs();
+            assert!(
+                error < 1e-12,
+                "error {:e} at {:?}, t = {}",
+                error,
+                x,
+                grid.time
+            );
+        }
+    }
+}
+
+#[test]
+fn spectral_solver_needs_periodic_faces() {
+    let mut grid = GridND::new(2, 17);
+    assert!(grid.set_spectral().is_err(), "dirichlet faces");
+    for side in [Side::Lower, Side::Upper] {
+        grid.boundaries.set(0, side, BoundaryCondition::Periodic);
+    }
+    assert!(grid.set_spectral().is_err(), "one axis left dirichlet");
+    for side in [Side::Lower, Side::Upper] {
+        grid.boundaries.set(1, side, BoundaryCondition::Neumann);
+    }
+    assert!(grid.set_spectral().is_err(), "one axis neumann");
+
+    let config = SimulationConfig {
+        dim: 2,
+        size: 17,
+        solver: Solver::Spectral,
+        ..SimulationConfig::default()
+    };
+    assert!(config.validate().is_err());
+    let periodic = SimulationConfig {
+        boundary: BoundaryCondition::Periodic,
+        ..config
+    };
+    periodic.validate().unwrap();
+}
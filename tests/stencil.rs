@@ -0,0 +1,80 @@
+use wave_equation_difference_between_3d_and_4d::{Field, GridND, Stencil};
+
+/// Error of the stencil's second derivative of `sin` at `x = 0.7` with spacing `h`.
+fn error(stencil: Stencil, h: f64) -> f64 {
+    let r = stencil.radius();
+    let mut u = Field::new(&[2 * r + 1]);
+    for i in 0..u.len() {
+        u[i] = (0.7 + (i as f64 - r as f64) * h).sin();
+    }
+    (stencil.interior_sum(&u, r) / (h * h) + 0.7_f64.sin()).abs()
+}
+
+#[test]
+fn stencils_converge_at_their_order() {
+    for stencil in Stencil::ALL {
+        let observed = (error(stencil, 0.4) / error(stencil, 0.2)).log2();
+        assert!(
+            (observed - stencil.order() as f64).abs() < 0.1,
+            "{}: observed order {}",
+            stencil,
+            observed
+        );
+    }
+}
+
+#[test]
+fn symbol_max_is_the_largest_symbol() {
+    let expected = [4.0, 16.0 / 3.0, 6.04, 6.50];
+    for (stencil, expected) in Stencil::ALL.into_iter().zip(expected) {
+        let c = stencil.coefficients();
+        let largest = (0..=1000)
+            .map(|j| {
+                let theta = std::f64::consts::PI * j as f64 / 1000.0;
+                let cosines: f64 = (1..c.len()).map(|k| c[k] * (k as f64 * theta).cos()).sum();
+                -(c[0] + 2.0 * cosines)
+            })
+            .fold(0.0, f64::max);
+        assert!(
+            (stencil.symbol_max() - largest).abs() < 1e-12,
+            "{}: {} vs {}",
+            stencil,
+            stencil.symbol_max(),
+            largest
+        );
+        assert!(
+            (stencil.symbol_max() - expected).abs() < 5e-3,
+            "{}",
+            stencil
+        );
+    }
+}
+
+/// Largest `|u|` of the README pulse on a 2D grid after 400 steps of `delta_t`.
+fn largest_after(stencil: Stencil, delta_t: f64) -> f64 {
+    let mut grid = GridND::new(2, 33);
+    grid.set_stencil(stencil);
+    grid.delta_t = delta_t;
+    grid.initialize();
+    for _ in 0..400 {
+        grid.step();
+    }
+    grid.x_1.data.iter().fold(0.0, |m, u| m.max(u.abs()))
+}
+
+#[test]
+fn cfl_limit_follows_the_symbol() {
+    for stencil in Stencil::ALL {
+        let mut grid = GridND::new(2, 33);
+        grid.set_stencil(stencil);
+        let limit = grid.max_time_step();
+        let expected = 2.0 * grid.delta_x / (2.0 * stencil.symbol_max()).sqrt();
+        assert!((limit - expected).abs() < 1e-15, "{}", stencil);
+        assert!(grid.set_time_step(1.01 * limit).is_err(), "{}", stencil);
+
+        let below = largest_after(stencil, 0.99 * limit);
+        assert!(below < 2.0, "{} below the limit: |u| = {}", stencil, below);
+        let above = largest_after(stencil, 1.05 * limit);
+        assert!(above > 1e3, "{} above the limit: |u| = {}", stencil, above);
+    }
+}